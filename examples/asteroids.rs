@@ -215,10 +215,8 @@ pub fn make_draw_callback(
             }
         }
 
-        let mut removed = 0;
-        for idx in projectiles_to_remove {
-            projectiles.remove(idx - removed);
-            removed += 1;
+        for (removed_count, i) in projectiles_to_remove.into_iter().enumerate() {
+            projectiles.remove(i - removed_count);
         }
 
         for (removed_count, i) in asteroids_to_remove.into_iter().enumerate() {
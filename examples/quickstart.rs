@@ -22,6 +22,13 @@ fn main() {
     renderer.run_infallible(move |frame, input| {
         let cursor_pos = frame.viewport().window_to_world(input.cursor_pos);
 
+        for (sprite, x) in [(i16x16, -1.), (i8x16, 1.)] {
+            frame
+                .draw_sprite(sprite)
+                .layer(BACKGROUND)
+                .pos([x, 0., 0.])
+                .done();
+        }
         frame
             .draw_sprite(i8x8)
             .layer(FOREGROUND)
@@ -0,0 +1,200 @@
+use chrono::Duration;
+
+use crate::{Renderer, SpriteIndex, TangerineError};
+
+/// What happens once an animation reaches its last frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AnimationMode {
+    /// Start over from the first frame.
+    #[default]
+    Loop,
+    /// Play the frames backwards and then forwards again, without
+    /// repeating the first and the last frames.
+    PingPong,
+    /// Stop at the last frame.
+    Once,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AnimationFrame {
+    pub sprite: SpriteIndex,
+    /// How long the frame is shown for
+    pub duration: Duration,
+}
+
+/// A sequence of sprites shown one after another.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Animation {
+    pub frames: Vec<AnimationFrame>,
+    pub mode: AnimationMode,
+}
+
+impl Animation {
+    /// An animation showing every sprite for the same duration.
+    pub fn uniform(
+        sprites: impl IntoIterator<Item = SpriteIndex>,
+        frame_duration: Duration,
+    ) -> Self {
+        Self {
+            frames: sprites
+                .into_iter()
+                .map(|sprite| AnimationFrame {
+                    sprite,
+                    duration: frame_duration,
+                })
+                .collect(),
+            mode: Default::default(),
+        }
+    }
+
+    pub fn mode(mut self, mode: AnimationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+/// Plays an animation, keeping track of the frame to show.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnimationPlayer {
+    animation: Animation,
+    frame: usize,
+    /// Time spent on the current frame
+    elapsed: Duration,
+    /// Whether a ping-pong animation is currently playing forwards
+    forwards: bool,
+    finished: bool,
+    paused: bool,
+}
+
+impl AnimationPlayer {
+    /// Fails if the animation has no frames.
+    pub fn new(animation: Animation) -> Result<Self, TangerineError> {
+        if animation.frames.is_empty() {
+            return Err(TangerineError::EmptyAnimation);
+        }
+
+        Ok(Self {
+            animation,
+            frame: 0,
+            elapsed: Duration::zero(),
+            forwards: true,
+            finished: false,
+            paused: false,
+        })
+    }
+
+    pub fn animation(&self) -> &Animation {
+        &self.animation
+    }
+
+    /// The sprite of the current frame.
+    pub fn sprite(&self) -> SpriteIndex {
+        self.animation.frames[self.frame].sprite
+    }
+
+    /// Index of the current frame in the animation.
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Whether an animation played once has reached its last frame.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Play the animation again from the first frame.
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.elapsed = Duration::zero();
+        self.forwards = true;
+        self.finished = false;
+    }
+
+    /// Advance by the time the renderer took to draw the last frame.
+    pub fn update(&mut self, renderer: &Renderer) {
+        self.advance(renderer.delta_time());
+    }
+
+    /// Advance the animation by the duration, skipping frames if needed.
+    pub fn advance(&mut self, delta_time: Duration) {
+        let total_duration = self
+            .animation
+            .frames
+            .iter()
+            .fold(Duration::zero(), |total, frame| total + frame.duration);
+        if self.paused || self.finished || total_duration <= Duration::zero() {
+            return;
+        }
+
+        self.elapsed = self.elapsed + delta_time;
+        // Whole loops end on the same frame, so they don't need to be stepped through
+        if self.animation.mode == AnimationMode::Loop && self.elapsed >= total_duration {
+            let rest = nanoseconds(self.elapsed) % nanoseconds(total_duration);
+            self.elapsed = Duration::seconds((rest / 1_000_000_000) as i64)
+                + Duration::nanoseconds((rest % 1_000_000_000) as i64);
+        }
+        while self.elapsed >= self.animation.frames[self.frame].duration {
+            self.elapsed = self.elapsed - self.animation.frames[self.frame].duration;
+            self.step();
+
+            if self.finished {
+                self.elapsed = Duration::zero();
+                return;
+            }
+        }
+    }
+
+    fn step(&mut self) {
+        let last = self.animation.frames.len() - 1;
+
+        match self.animation.mode {
+            AnimationMode::Loop => {
+                self.frame = if self.frame == last {
+                    0
+                } else {
+                    self.frame + 1
+                }
+            }
+            AnimationMode::Once if self.frame == last => self.finished = true,
+            AnimationMode::Once => self.frame += 1,
+            AnimationMode::PingPong if last == 0 => {}
+            AnimationMode::PingPong => {
+                if (self.forwards && self.frame == last) || (!self.forwards && self.frame == 0) {
+                    self.forwards = !self.forwards;
+                }
+
+                if self.forwards {
+                    self.frame += 1;
+                } else {
+                    self.frame -= 1;
+                }
+            }
+        }
+    }
+}
+
+/// The whole duration in nanoseconds, which doesn't fit into an `i64` for
+/// durations of a few hundred years.
+fn nanoseconds(duration: Duration) -> i128 {
+    let seconds = duration.num_seconds();
+    let subsec_nanos = (duration - Duration::seconds(seconds))
+        .num_nanoseconds()
+        .unwrap_or(0);
+    seconds as i128 * 1_000_000_000 + subsec_nanos as i128
+}
+
+impl TryFrom<Animation> for AnimationPlayer {
+    type Error = TangerineError;
+
+    fn try_from(animation: Animation) -> Result<Self, Self::Error> {
+        Self::new(animation)
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     packing::Node, vertex::Vertex, Renderer, SpriteDrawData, SpriteIndex, SpriteLoadOptions,
+    TangerineError,
 };
 use image::{DynamicImage, RgbaImage};
 use mint::Vector2;
@@ -9,230 +10,334 @@ use wgpu::util::DeviceExt;
 #[must_use]
 pub struct AtlasBuilder<'renderer, const N: usize> {
     pub(crate) rgba: Vec<RgbaImage>,
+    pub(crate) options: Vec<SpriteLoadOptions>,
     pub(crate) renderer: &'renderer mut Renderer,
 
+    /// Options used for sprites added without specifying any
+    pub(crate) default_options: SpriteLoadOptions,
+
     pub(crate) dynamically_dispatched_sprites: Vec<SpriteIndex>,
     pub(crate) statically_dispatched_sprites: [SpriteIndex; N],
+
+    /// Names registered for the sprites, applied once the atlas is finalized
+    pub(crate) names: Vec<(String, SpriteIndex)>,
 }
 
-impl<'me, const N: usize> AtlasBuilder<'me, N> {
-    #[must_use]
-    pub fn finalize_and_repack(mut self) -> [SpriteIndex; N] {
-        self.rgba.sort_by(|lhs, rhs| {
-            lhs.width()
-                .cmp(&rhs.width())
-                .then(lhs.height().cmp(&rhs.height()))
-                .reverse()
-        });
+/// Every sprite loaded into the atlas and where it is placed, kept on the CPU
+/// so that new sprites can be added without losing the old ones.
+#[derive(Debug)]
+pub(crate) struct AtlasContents {
+    /// The sprites' images, indexed by their `SpriteIndex`
+    pub(crate) images: Vec<RgbaImage>,
+    /// The options each sprite was loaded with
+    pub(crate) options: Vec<SpriteLoadOptions>,
+    /// Top left corner of each sprite's footprint in its page
+    pub(crate) positions: Vec<Vector2<u32>>,
+    /// The page each sprite is placed onto
+    pub(crate) sprite_pages: Vec<usize>,
+    /// Pages the sprites spill into once they no longer fit into one texture
+    pub(crate) pages: Vec<AtlasPage>,
+    /// The largest width and height of a page's texture
+    pub(crate) max_page_size: u32,
+    /// Sprites registered under a name to be looked up later
+    pub(crate) names: hashbrown::HashMap<String, SpriteIndex>,
+}
 
-        let mut root = Node {
-            children: None,
-            topleft: [0; 2].into(),
-            botright: [u32::MAX; 2].into(),
-            image_index: None,
-        };
+/// A single texture of the atlas along with its free space.
+#[derive(Debug)]
+pub(crate) struct AtlasPage {
+    /// The packing tree new sprites are inserted into
+    pub(crate) packer: Node,
+    /// Size of the page's texture currently on the GPU
+    pub(crate) texture_size: Vector2<u32>,
+}
 
-        for (i, image) in self.rgba.iter().enumerate() {
-            root.insert(image, i);
+/// The GPU side of an atlas page.
+pub(crate) struct AtlasPageTexture {
+    pub(crate) texture: wgpu::Texture,
+    pub(crate) bind_group: wgpu::BindGroup,
+}
+
+impl AtlasContents {
+    pub(crate) fn new(max_page_size: u32) -> Self {
+        Self {
+            images: vec![],
+            options: vec![],
+            positions: vec![],
+            sprite_pages: vec![],
+            pages: vec![],
+            max_page_size,
+            names: [].into(),
         }
+    }
 
-        let mut node_queue = vec![root];
-        let mut sprites_to_put = vec![];
-        while let Some(node) = node_queue.pop() {
-            let Node {
-                children,
-                topleft,
-                image_index,
-                ..
-            } = node;
-
-            if let Some([left, right]) = children {
-                node_queue.push(left.into_inner());
-                node_queue.push(right.into_inner());
+    /// Insert the sprite into the first page with enough free space, opening
+    /// a new page if none of them have it. The sprite has to fit into an
+    /// empty page, which the atlas builder checks before placing anything.
+    pub(crate) fn place(&mut self, idx: SpriteIndex) {
+        let footprint = self.footprint(idx);
+        debug_assert!(footprint.x <= self.max_page_size && footprint.y <= self.max_page_size);
+
+        for (page_idx, page) in self.pages.iter_mut().enumerate() {
+            if let Some(position) = page.packer.insert(footprint, idx) {
+                self.positions[idx] = position;
+                self.sprite_pages[idx] = page_idx;
+                return;
             }
+        }
 
-            if let Some(idx) = image_index {
-                sprites_to_put.push((topleft, idx))
-            }
+        let mut page = AtlasPage {
+            packer: Node::root(self.max_page_size),
+            texture_size: [0; 2].into(),
+        };
+        self.positions[idx] = page.packer.insert(footprint, idx).unwrap();
+        self.sprite_pages[idx] = self.pages.len();
+        self.pages.push(page);
+    }
+
+    /// How much space the sprite takes in the atlas, including the padding.
+    pub(crate) fn footprint(&self, idx: SpriteIndex) -> Vector2<u32> {
+        let image = &self.images[idx];
+        self.options[idx]
+            .footprint(image.width(), image.height())
+            .into()
+    }
+
+    /// Top left corner of the sprite's own pixels, inside of the extruded edges.
+    pub(crate) fn content_position(&self, idx: SpriteIndex) -> Vector2<u32> {
+        let (position, extrude) = (self.positions[idx], self.options[idx].extrude);
+        [position.x + extrude, position.y + extrude].into()
+    }
+
+    /// The sprite's image with its edges extruded, as it is stored in the atlas.
+    pub(crate) fn extruded_image(&self, idx: SpriteIndex) -> RgbaImage {
+        let (image, extrude) = (&self.images[idx], self.options[idx].extrude);
+        if extrude == 0 {
+            return image.clone();
         }
 
-        let buffer_size: Vector2<u32> = sprites_to_put
-            .iter()
-            .map(|(pos, idx)| {
-                let z = &self.rgba[*idx];
-                [pos.x + z.width(), pos.y + z.height()]
+        let [width, height] = self.options[idx].extruded_size(image.width(), image.height());
+        RgbaImage::from_fn(width, height, |x, y| {
+            *image.get_pixel(
+                x.saturating_sub(extrude).min(image.width() - 1),
+                y.saturating_sub(extrude).min(image.height() - 1),
+            )
+        })
+    }
+
+    /// The smallest size the page's texture has to be to fit its sprites.
+    pub(crate) fn page_extents(&self, page: usize) -> Vector2<u32> {
+        (0..self.images.len())
+            .filter(|idx| self.sprite_pages[*idx] == page)
+            .map(|idx| {
+                let (position, image) = (self.positions[idx], &self.images[idx]);
+                let [width, height] =
+                    self.options[idx].extruded_size(image.width(), image.height());
+                [position.x + width, position.y + height]
             })
-            .reduce(|[x1, y1], [x2, y2]| [x1.max(x2), y1.max(y2)])
-            .unwrap()
-            .into();
+            .fold([1, 1], |[x1, y1], [x2, y2]| [x1.max(x2), y1.max(y2)])
+            .into()
+    }
+}
 
-        let (width, height) = (buffer_size.x, buffer_size.y);
+impl<'me, const N: usize> AtlasBuilder<'me, N> {
+    /// Insert the new sprites into the free space of the atlas, only
+    /// uploading the new sprites unless a page's texture has to grow.
+    ///
+    /// # Panics
+    ///
+    /// If a sprite with its padding is larger than the maximum texture size,
+    /// see `AtlasBuilder::try_finalize`.
+    #[must_use]
+    pub fn finalize(self) -> [SpriteIndex; N] {
+        self.try_finalize().unwrap()
+    }
 
-        let mut buffer = RgbaImage::new(buffer_size.x, buffer_size.y);
-        let mut vertices = vec![];
-        let mut indices = vec![];
-        let mut sprites = vec![];
+    /// Same as `AtlasBuilder::finalize` but fails instead of panicking,
+    /// leaving the atlas untouched.
+    pub fn try_finalize(self) -> Result<[SpriteIndex; N], TangerineError> {
+        self.check_sizes()?;
 
-        for (pos, idx) in sprites_to_put.iter() {
-            let sprite = &self.rgba[*idx];
-            for (row, new_row) in buffer.rows_mut().skip(pos.y as _).zip(sprite.rows()) {
-                for (pixel, new_color) in row.skip(pos.x as _).zip(new_row) {
-                    *pixel = *new_color;
-                }
-            }
+        let AtlasBuilder {
+            rgba,
+            options,
+            renderer,
+            statically_dispatched_sprites,
+            names,
+            ..
+        } = self;
 
-            let x_step = 1. / width as f32;
-            let y_step = 1. / height as f32;
-            let x = pos.x as f32 / width as f32;
-            let y = pos.y as f32 / height as f32;
+        let contents = &mut renderer.atlas_contents;
+        contents.names.extend(names);
+        let first_new_sprite = contents.images.len();
 
-            let sprite_aspect_ratio = sprite.width() as f32 / sprite.height() as f32;
+        for (image, options) in rgba.into_iter().zip(options) {
+            contents.images.push(image);
+            contents.options.push(options);
+            contents.positions.push([0; 2].into());
+            contents.sprite_pages.push(0);
+            contents.place(contents.images.len() - 1);
+        }
 
-            let verts = [
-                Vertex {
-                    position: [-0.5, -0.5, 0.0],
-                    tex_coords: [x, y + y_step * sprite.height() as f32],
-                },
-                Vertex {
-                    position: [0.5, -0.5, 0.0],
-                    tex_coords: [
-                        x + x_step * sprite.width() as f32,
-                        y + y_step * sprite.height() as f32,
-                    ],
-                },
-                Vertex {
-                    position: [-0.5, 0.5, 0.0],
-                    tex_coords: [x, y],
-                },
-                Vertex {
-                    position: [0.5, 0.5, 0.0],
-                    tex_coords: [x + x_step * sprite.width() as f32, y],
-                },
+        let mut grown_pages = vec![];
+        for (page_idx, page) in contents.pages.iter().enumerate() {
+            let extents = contents.page_extents(page_idx);
+            if extents.x > page.texture_size.x || extents.y > page.texture_size.y {
+                grown_pages.push(page_idx);
+            }
+        }
+
+        for page_idx in grown_pages.iter().copied() {
+            let contents = &mut renderer.atlas_contents;
+            let extents = contents.page_extents(page_idx);
+            // Grow in powers of two to not reallocate on every new sprite
+            contents.pages[page_idx].texture_size = [
+                extents.x.next_power_of_two().min(contents.max_page_size),
+                extents.y.next_power_of_two().min(contents.max_page_size),
             ]
-            .map(|vert| {
-                let [x, y, z] = vert.position;
+            .into();
+            renderer.upload_atlas_page(page_idx);
+        }
 
-                Vertex {
-                    position: [x * sprite_aspect_ratio, y, z],
-                    ..vert
-                }
-            });
+        let new_sprites = first_new_sprite..renderer.atlas_contents.images.len();
+        renderer.write_atlas_sprites(
+            new_sprites
+                .filter(|idx| !grown_pages.contains(&renderer.atlas_contents.sprite_pages[*idx])),
+        );
 
-            let inds = [0, 1, 2, 1, 3, 2].map(|i| i + vertices.len() as u32);
+        renderer.rebuild_sprite_meshes();
+        Ok(statically_dispatched_sprites)
+    }
 
-            sprites.push(SpriteDrawData {
-                sprite_index_range: (indices.len() as u32, (indices.len() + inds.len()) as u32),
-            });
-            indices.extend(inds);
-            vertices.extend(verts);
-        }
+    /// Insert the new sprites and then pack every sprite in the atlas from
+    /// scratch, re-uploading the whole atlas. The indices of the sprites
+    /// loaded earlier are kept.
+    ///
+    /// # Panics
+    ///
+    /// If a sprite with its padding is larger than the maximum texture size,
+    /// see `AtlasBuilder::try_finalize_and_repack`.
+    #[must_use]
+    pub fn finalize_and_repack(self) -> [SpriteIndex; N] {
+        self.try_finalize_and_repack().unwrap()
+    }
 
-        let texture_size = wgpu::Extent3d {
-            width,
-            height,
-            depth_or_array_layers: 1,
-        };
+    /// Same as `AtlasBuilder::finalize_and_repack` but fails instead of
+    /// panicking, leaving the atlas untouched.
+    pub fn try_finalize_and_repack(self) -> Result<[SpriteIndex; N], TangerineError> {
+        self.check_sizes()?;
 
-        let texture = self
-            .renderer
-            .device()
-            .create_texture(&wgpu::TextureDescriptor {
-                size: texture_size,
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rgba8UnormSrgb,
-                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-                label: None,
-                view_formats: &[],
-            });
+        let AtlasBuilder {
+            rgba,
+            options,
+            renderer,
+            statically_dispatched_sprites,
+            names,
+            ..
+        } = self;
 
-        self.renderer.queue().write_texture(
-            wgpu::ImageCopyTexture {
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            &buffer,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * width),
-                rows_per_image: Some(height),
-            },
-            texture_size,
-        );
+        let contents = &mut renderer.atlas_contents;
+        contents.names.extend(names);
+        contents.images.extend(rgba);
+        contents.options.extend(options);
 
-        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut order = (0..contents.images.len()).collect::<Vec<_>>();
+        order.sort_by(|lhs, rhs| {
+            let (lhs, rhs) = (contents.footprint(*lhs), contents.footprint(*rhs));
+            lhs.x.cmp(&rhs.x).then(lhs.y.cmp(&rhs.y)).reverse()
+        });
 
-        let texture_bind_group_layout = &self.renderer.atlas_bind_group_layout;
+        contents.pages.clear();
+        contents.positions = vec![[0; 2].into(); contents.images.len()];
+        contents.sprite_pages = vec![0; contents.images.len()];
+        for idx in order {
+            contents.place(idx);
+        }
 
-        self.renderer.index_buffer =
-            self.renderer
-                .device()
-                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: None,
-                    contents: bytemuck::cast_slice(&indices),
-                    usage: wgpu::BufferUsages::INDEX,
-                });
+        renderer.atlas_pages.clear();
+        for page_idx in 0..renderer.atlas_contents.pages.len() {
+            let contents = &mut renderer.atlas_contents;
+            contents.pages[page_idx].texture_size = contents.page_extents(page_idx);
+            renderer.upload_atlas_page(page_idx);
+        }
+        renderer.rebuild_sprite_meshes();
 
-        self.renderer.vertex_buffer =
-            self.renderer
-                .device()
-                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: None,
-                    contents: bytemuck::cast_slice(&vertices),
-                    usage: wgpu::BufferUsages::VERTEX,
-                });
+        Ok(statically_dispatched_sprites)
+    }
 
-        let bind_group = self
-            .renderer
-            .device()
-            .create_bind_group(&wgpu::BindGroupDescriptor {
-                layout: texture_bind_group_layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: wgpu::BindingResource::TextureView(&texture_view),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: wgpu::BindingResource::Sampler(&self.renderer.atlas_sampler),
-                    },
-                ],
-                label: None,
-            });
+    /// Make sure every new sprite has pixels and fits into an empty page
+    /// before any of them is placed.
+    fn check_sizes(&self) -> Result<(), TangerineError> {
+        let max_size = self.renderer.atlas_contents.max_page_size;
+        let first_new_sprite = self.renderer.atlas_contents.images.len();
 
-        self.renderer.atlas_texture = (texture, texture_view);
-        self.renderer.cold_bind_group = bind_group;
-        self.renderer.sprites = sprites;
+        for (offset, (image, options)) in self.rgba.iter().zip(&self.options).enumerate() {
+            if image.width() == 0 || image.height() == 0 {
+                return Err(TangerineError::EmptySprite(first_new_sprite + offset));
+            }
+
+            let [width, height] = options.footprint(image.width(), image.height());
+            if width > max_size || height > max_size {
+                return Err(TangerineError::SpriteTooLarge {
+                    sprite: first_new_sprite + offset,
+                    width,
+                    height,
+                    max_size,
+                });
+            }
+        }
+        Ok(())
+    }
 
-        self.statically_dispatched_sprites.reverse();
-        self.statically_dispatched_sprites
+    /// Options used by the sprites added without specifying any, for
+    /// example to pad every sprite of the atlas.
+    pub fn default_options(mut self, options: impl Into<SpriteLoadOptions>) -> Self {
+        self.default_options = options.into();
+        self
     }
 
     /// Add a sprite into the queue later to be stitched (and maybe packed).
     pub fn add_sprite(self, image: impl Into<DynamicImage>) -> AtlasBuilder<'me, { N + 1 }> {
-        self.add_sprite_advanced(
-            image,
-            SpriteLoadOptions {
-                ..Default::default()
-            },
-        )
+        let options = self.default_options;
+        self.add_sprite_advanced(image, options)
     }
 
     /// Add a sprite to the dynamically dispatched queue without adding it to
     /// the list returned by `.finalize()` / `.finalize_and_repack()`.
     pub fn add_sprite_dynamically(mut self, image: impl Into<DynamicImage>) -> (Self, SpriteIndex) {
-        self.rgba.push(image.into().into_rgba8());
+        let options = self.default_options;
+        let new_idx = self.queue_sprite(load_image(image.into(), &options), options);
+        (self, new_idx)
+    }
 
-        let new_idx = self.statically_dispatched_sprites.len()
-            + self.dynamically_dispatched_sprites.len()
-            + self.renderer.sprites.len();
+    /// Queue an already converted image as a dynamically dispatched sprite,
+    /// failing right away if the image has no pixels.
+    pub(crate) fn push_sprite(
+        &mut self,
+        image: RgbaImage,
+        options: SpriteLoadOptions,
+    ) -> Result<SpriteIndex, TangerineError> {
+        if image.width() == 0 || image.height() == 0 {
+            return Err(TangerineError::EmptySprite(self.next_index()));
+        }
+        Ok(self.queue_sprite(image, options))
+    }
 
+    /// Queue the image without checking it, empty images are only reported
+    /// once the atlas is finalized.
+    fn queue_sprite(&mut self, image: RgbaImage, options: SpriteLoadOptions) -> SpriteIndex {
+        let new_idx = self.next_index();
+        self.rgba.push(image);
+        self.options.push(options);
         self.dynamically_dispatched_sprites.push(new_idx);
+        new_idx
+    }
 
-        (self, new_idx)
+    /// The index the next queued sprite gets.
+    fn next_index(&self) -> SpriteIndex {
+        self.statically_dispatched_sprites.len()
+            + self.dynamically_dispatched_sprites.len()
+            + self.renderer.atlas_contents.images.len()
     }
 
     /// Add a sprite with options not activated by default
@@ -243,22 +348,15 @@ impl<'me, const N: usize> AtlasBuilder<'me, N> {
     ) -> AtlasBuilder<'me, { N + 1 }> {
         let AtlasBuilder {
             mut rgba,
+            options: mut all_options,
             renderer,
+            default_options,
             statically_dispatched_sprites,
             dynamically_dispatched_sprites,
-            ..
+            names,
         } = self;
 
-        let SpriteLoadOptions { premultiplied, .. } = options.into();
-        let mut rgba32f = image.into().to_rgba32f();
-
-        if premultiplied {
-            for [r, g, b, a] in rgba32f.iter_mut().array_chunks::<4>() {
-                *r /= *a;
-                *g /= *a;
-                *b /= *a;
-            }
-        }
+        let options = options.into();
 
         let mut sprites_iterator =
             statically_dispatched_sprites
@@ -266,15 +364,237 @@ impl<'me, const N: usize> AtlasBuilder<'me, N> {
                 .chain(std::iter::once(
                     statically_dispatched_sprites.len()
                         + dynamically_dispatched_sprites.len()
-                        + renderer.sprites.len(),
+                        + renderer.atlas_contents.images.len(),
                 ));
-        rgba.push(DynamicImage::from(rgba32f).to_rgba8());
+        rgba.push(load_image(image.into(), &options));
+        all_options.push(options);
 
         AtlasBuilder {
             rgba,
+            options: all_options,
             renderer,
+            default_options,
             statically_dispatched_sprites: [(); N + 1].map(|_| sprites_iterator.next().unwrap()),
             dynamically_dispatched_sprites,
+            names,
+        }
+    }
+
+    /// Add a sprite that can be looked up by its name with `Renderer::sprite(..)`
+    /// once the atlas is finalized.
+    pub fn add_named_sprite(
+        self,
+        name: impl Into<String>,
+        image: impl Into<DynamicImage>,
+    ) -> AtlasBuilder<'me, { N + 1 }> {
+        let mut builder = self.add_sprite(image);
+        let new_idx = builder.statically_dispatched_sprites[N];
+        builder.names.push((name.into(), new_idx));
+        builder
+    }
+
+    /// Register an already added sprite under the name, replacing the sprite
+    /// previously registered under it.
+    pub fn name_sprite(mut self, sprite: SpriteIndex, name: impl Into<String>) -> Self {
+        self.names.push((name.into(), sprite));
+        self
+    }
+}
+
+/// Convert the image into the format stored in the atlas.
+pub(crate) fn load_image(image: DynamicImage, options: &SpriteLoadOptions) -> RgbaImage {
+    if !options.premultiplied {
+        return image.into_rgba8();
+    }
+
+    let mut rgba32f = image.to_rgba32f();
+    for [r, g, b, a] in rgba32f.iter_mut().array_chunks::<4>() {
+        *r /= *a;
+        *g /= *a;
+        *b /= *a;
+    }
+
+    DynamicImage::from(rgba32f).to_rgba8()
+}
+
+impl Renderer {
+    /// Recreate the texture of the page with the size stored in the atlas
+    /// contents and upload every sprite of the page into it.
+    pub(crate) fn upload_atlas_page(&mut self, page_idx: usize) {
+        let Vector2 {
+            x: width,
+            y: height,
+        } = self.atlas_contents.pages[page_idx].texture_size;
+
+        let texture = self.device().create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            label: None,
+            view_formats: &[],
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let bind_group = self.device().create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.atlas_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.atlas_sampler),
+                },
+            ],
+            label: None,
+        });
+
+        let page = AtlasPageTexture {
+            texture,
+            bind_group,
+        };
+
+        if page_idx < self.atlas_pages.len() {
+            self.atlas_pages[page_idx] = page;
+        } else {
+            self.atlas_pages.push(page);
+        }
+
+        let contents = &self.atlas_contents;
+        self.write_atlas_sprites(
+            (0..contents.images.len()).filter(|idx| contents.sprite_pages[*idx] == page_idx),
+        );
+    }
+
+    /// Copy the sprites into their place in their page's texture.
+    pub(crate) fn write_atlas_sprites(&self, sprites: impl Iterator<Item = SpriteIndex>) {
+        let contents = &self.atlas_contents;
+
+        for idx in sprites {
+            let (image, position) = (contents.extruded_image(idx), contents.positions[idx]);
+
+            self.queue().write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &self.atlas_pages[contents.sprite_pages[idx]].texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: position.x,
+                        y: position.y,
+                        z: 0,
+                    },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &image,
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(4 * image.width()),
+                    rows_per_image: Some(image.height()),
+                },
+                wgpu::Extent3d {
+                    width: image.width(),
+                    height: image.height(),
+                    depth_or_array_layers: 1,
+                },
+            );
         }
     }
+
+    /// Regenerate the meshes of every sprite, the texture coordinates depend
+    /// on the size of the sprite's page.
+    pub(crate) fn rebuild_sprite_meshes(&mut self) {
+        let contents = &self.atlas_contents;
+
+        let mut vertices = vec![];
+        let mut indices = vec![];
+        let mut sprites = vec![];
+
+        for (idx, sprite) in contents.images.iter().enumerate() {
+            let page = contents.sprite_pages[idx];
+            // The texture coordinates only cover the sprite, not its extruded edges
+            let pos = contents.content_position(idx);
+            let Vector2 {
+                x: width,
+                y: height,
+            } = contents.pages[page].texture_size;
+
+            let x = pos.x as f32 / width as f32;
+            let y = pos.y as f32 / height as f32;
+            let tex_size = [
+                sprite.width() as f32 / width as f32,
+                sprite.height() as f32 / height as f32,
+            ];
+
+            let quad_size = match contents.options[idx]
+                .pixels_per_unit
+                .or(self.pixels_per_unit)
+            {
+                Some(pixels_per_unit) => [
+                    sprite.width() as f32 / pixels_per_unit,
+                    sprite.height() as f32 / pixels_per_unit,
+                ],
+                None => [sprite.width() as f32 / sprite.height() as f32, 1.],
+            };
+
+            let [pivot_x, pivot_y] = contents.options[idx].pivot;
+            let [left, top, right, bottom] = contents.options[idx].nine_slice.unwrap_or_default();
+            let borders = [
+                left as f32 / sprite.width() as f32,
+                top as f32 / sprite.height() as f32,
+                right as f32 / sprite.width() as f32,
+                bottom as f32 / sprite.height() as f32,
+            ];
+
+            // Corners are in the orientation of the texture, starting at the top left
+            let verts = [[0., 1.], [1., 1.], [0., 0.], [1., 0.]].map(|corner: [f32; 2]| Vertex {
+                position: [
+                    (corner[0] - pivot_x) * quad_size[0],
+                    (pivot_y - corner[1]) * quad_size[1],
+                    0.0,
+                ],
+                tex_coords: [x + corner[0] * tex_size[0], y + corner[1] * tex_size[1]],
+                corner,
+                quad_size,
+                tex_size,
+            });
+
+            let inds = [0, 1, 2, 1, 3, 2].map(|i| i + vertices.len() as u32);
+
+            sprites.push(SpriteDrawData {
+                sprite_index_range: (indices.len() as u32, (indices.len() + inds.len()) as u32),
+                page,
+                quad_size,
+                pivot: [pivot_x, pivot_y],
+                borders,
+            });
+            indices.extend(inds);
+            vertices.extend(verts);
+        }
+
+        self.index_buffer = self
+            .device()
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: bytemuck::cast_slice(&indices),
+                usage: wgpu::BufferUsages::INDEX,
+            });
+
+        self.vertex_buffer = self
+            .device()
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: bytemuck::cast_slice(&vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+
+        self.sprites = sprites;
+    }
 }
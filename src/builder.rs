@@ -0,0 +1,288 @@
+use cint::EncodedSrgb;
+use winit::{dpi::PhysicalSize, window::Window};
+
+use crate::{
+    renderer::{RenderTarget, OFFSCREEN_TEXTURE_FORMAT},
+    Renderer, TangerineError,
+};
+
+/// How the presentation of frames is synchronized with the display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum VSync {
+    /// Wait for the vertical blank, never tears.
+    #[default]
+    On,
+    /// Present as soon as possible, may tear.
+    Off,
+    /// Replace the queued frame with the newest one, falls back to `On`
+    /// if the surface doesn't support it.
+    Mailbox,
+}
+
+/// Configuration of the adapter, the presentation and the pipeline used to
+/// create a [`Renderer`].
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub struct RendererBuilder {
+    pub(crate) backends: wgpu::Backends,
+    pub(crate) power_preference: wgpu::PowerPreference,
+    pub(crate) adapter_name: Option<String>,
+    pub(crate) vsync: VSync,
+    pub(crate) features: wgpu::Features,
+    pub(crate) limits: Option<wgpu::Limits>,
+    pub(crate) sample_count: u32,
+    pub(crate) clear_color: Option<EncodedSrgb<u8>>,
+    pub(crate) pixels_per_unit: Option<f32>,
+}
+
+impl Default for RendererBuilder {
+    fn default() -> Self {
+        Self {
+            backends: wgpu::Backends::all(),
+            power_preference: Default::default(),
+            adapter_name: None,
+            vsync: Default::default(),
+            features: wgpu::Features::empty(),
+            limits: None,
+            sample_count: 1,
+            clear_color: None,
+            pixels_per_unit: None,
+        }
+    }
+}
+
+impl RendererBuilder {
+    /// The set of backends adapters are looked up in.
+    pub fn backends(mut self, backends: wgpu::Backends) -> Self {
+        self.backends = backends;
+        self
+    }
+
+    /// Whether a low power or a high performance adapter is preferred.
+    pub fn power_preference(mut self, power_preference: wgpu::PowerPreference) -> Self {
+        self.power_preference = power_preference;
+        self
+    }
+
+    /// Only use the adapter which name contains the provided string.
+    pub fn adapter_name(mut self, name: impl Into<String>) -> Self {
+        self.adapter_name = Some(name.into());
+        self
+    }
+
+    pub fn vsync(mut self, vsync: VSync) -> Self {
+        self.vsync = vsync;
+        self
+    }
+
+    /// Features the device is required to have.
+    pub fn features(mut self, features: wgpu::Features) -> Self {
+        self.features = features;
+        self
+    }
+
+    /// Limits the device is required to satisfy, by default these are the
+    /// defaults of wgpu (or WebGL2 ones on the web).
+    pub fn limits(mut self, limits: wgpu::Limits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// The amount of samples per pixel, `1` disables multisampling.
+    pub fn sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    /// The background color to clear the target with, when `None` the
+    /// target is not cleared.
+    pub fn clear_color(mut self, clear_color: Option<impl Into<EncodedSrgb<u8>>>) -> Self {
+        self.clear_color = clear_color.map(Into::into);
+        self
+    }
+
+    /// How many pixels of a sprite span a world unit, so that sprites keep
+    /// their relative sizes. By default every sprite is one unit tall.
+    pub fn pixels_per_unit(mut self, pixels_per_unit: f32) -> Self {
+        self.pixels_per_unit = Some(pixels_per_unit);
+        self
+    }
+}
+
+#[cfg(feature = "sync-new")]
+impl RendererBuilder {
+    /// Creates a renderer presenting onto the window.
+    pub fn build(self, window: Window) -> Result<Renderer, TangerineError> {
+        pollster::block_on(self.build_async(window))
+    }
+
+    /// Creates a renderer that draws into an offscreen texture of the given size.
+    pub fn build_headless(
+        self,
+        size: impl Into<PhysicalSize<u32>>,
+    ) -> Result<Renderer, TangerineError> {
+        pollster::block_on(self.build_headless_async(size))
+    }
+}
+
+impl RendererBuilder {
+    /// Creates all the required objects on the GPU to draw onto the Window
+    pub async fn build_async(self, window: Window) -> Result<Renderer, TangerineError> {
+        let size = window.inner_size();
+
+        let instance = self.instance();
+        let surface = unsafe { instance.create_surface(&window) }?;
+
+        let adapter = self.adapter(&instance, Some(&surface)).await?;
+        let (device, queue) = self.request_device(&adapter).await?;
+
+        let surface_caps = surface.get_capabilities(&adapter);
+        let surface_format = surface_caps
+            .formats
+            .iter()
+            .copied()
+            .find(|f| f.is_srgb())
+            .unwrap_or(surface_caps.formats[0]);
+        self.check_sample_count(&adapter, surface_format)?;
+
+        let present_mode = match self.vsync {
+            VSync::On => wgpu::PresentMode::AutoVsync,
+            VSync::Off => wgpu::PresentMode::AutoNoVsync,
+            VSync::Mailbox
+                if surface_caps
+                    .present_modes
+                    .contains(&wgpu::PresentMode::Mailbox) =>
+            {
+                wgpu::PresentMode::Mailbox
+            }
+            VSync::Mailbox => wgpu::PresentMode::AutoVsync,
+        };
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: surface_format,
+            width: size.width,
+            height: size.height,
+            present_mode,
+            alpha_mode: surface_caps.alpha_modes[0],
+            view_formats: vec![],
+        };
+        surface.configure(&device, &config);
+
+        Ok(Renderer::from_target(
+            device,
+            queue,
+            size,
+            RenderTarget::Window {
+                surface,
+                config,
+                window,
+            },
+            &self,
+        ))
+    }
+
+    /// Creates all the required objects on the GPU to draw into an offscreen
+    /// texture, falling back onto a software adapter if there is no GPU.
+    pub async fn build_headless_async(
+        self,
+        size: impl Into<PhysicalSize<u32>>,
+    ) -> Result<Renderer, TangerineError> {
+        let size = size.into();
+
+        let instance = self.instance();
+        let adapter = self.adapter(&instance, None).await?;
+        self.check_sample_count(&adapter, OFFSCREEN_TEXTURE_FORMAT)?;
+
+        let (device, queue) = self.request_device(&adapter).await?;
+        let texture = Renderer::create_target_texture(&device, size, OFFSCREEN_TEXTURE_FORMAT);
+
+        Ok(Renderer::from_target(
+            device,
+            queue,
+            size,
+            RenderTarget::Offscreen { texture },
+            &self,
+        ))
+    }
+
+    fn instance(&self) -> wgpu::Instance {
+        wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: self.backends,
+            dx12_shader_compiler: Default::default(),
+        })
+    }
+
+    /// Find the adapter by name if one was requested, otherwise let wgpu pick
+    /// one and fall back onto a software adapter if there is none.
+    async fn adapter(
+        &self,
+        instance: &wgpu::Instance,
+        surface: Option<&wgpu::Surface>,
+    ) -> Result<wgpu::Adapter, TangerineError> {
+        let is_compatible =
+            |adapter: &wgpu::Adapter| surface.is_none_or(|s| adapter.is_surface_supported(s));
+
+        if let Some(name) = &self.adapter_name {
+            return instance
+                .enumerate_adapters(self.backends)
+                .find(|adapter| adapter.get_info().name.contains(name) && is_compatible(adapter))
+                .ok_or(TangerineError::NoCompatibleAdapter);
+        }
+
+        for force_fallback_adapter in [false, true] {
+            if let Some(adapter) = instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference: self.power_preference,
+                    force_fallback_adapter,
+                    compatible_surface: surface,
+                })
+                .await
+            {
+                return Ok(adapter);
+            }
+        }
+
+        Err(TangerineError::NoCompatibleAdapter)
+    }
+
+    async fn request_device(
+        &self,
+        adapter: &wgpu::Adapter,
+    ) -> Result<(wgpu::Device, wgpu::Queue), TangerineError> {
+        adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    features: self.features,
+                    limits: self.limits.clone().unwrap_or_else(|| {
+                        if cfg!(target_arch = "wasm32") {
+                            wgpu::Limits::downlevel_webgl2_defaults()
+                        } else {
+                            wgpu::Limits::default()
+                        }
+                    }),
+                    label: None,
+                },
+                None,
+            )
+            .await
+            .map_err(TangerineError::from)
+    }
+
+    fn check_sample_count(
+        &self,
+        adapter: &wgpu::Adapter,
+        format: wgpu::TextureFormat,
+    ) -> Result<(), TangerineError> {
+        if self.sample_count == 1
+            || adapter
+                .get_texture_format_features(format)
+                .flags
+                .sample_count_supported(self.sample_count)
+        {
+            Ok(())
+        } else {
+            Err(TangerineError::UnsupportedSampleCount(self.sample_count))
+        }
+    }
+}
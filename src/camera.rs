@@ -1,5 +1,7 @@
 use bytemuck::{Pod, Zeroable};
+use chrono::Duration;
 use mint::{ColumnMatrix4, Vector2};
+use winit::dpi::PhysicalSize;
 
 /// An orthographic camera. Projecting into the world.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -10,6 +12,15 @@ pub struct Camera {
     pub size: f32,
     /// Aspect ratio of the viewport everything is rendered onto
     pub aspect_ratio: f32,
+    /// Snap the position to the pixel grid of sprites with this many pixels
+    /// per unit, so that they don't shimmer while the camera moves.
+    pub pixel_snapping: Option<f32>,
+    /// Rotation of the camera in rad in the direction sprites are rotated in,
+    /// the world appears rotated the other way.
+    pub rotation_rad: f32,
+    /// Magnification on top of the size, values above 1 zoom in.
+    pub zoom: f32,
+    pub shake: CameraShake,
 }
 
 impl Default for Camera {
@@ -18,10 +29,142 @@ impl Default for Camera {
             position: [0., 0.].into(),
             size: 3.,
             aspect_ratio: 1.,
+            pixel_snapping: None,
+            rotation_rad: 0.,
+            zoom: 1.,
+            shake: Default::default(),
         }
     }
 }
 
+/// Name of the camera every renderer starts with. It can't be removed but
+/// its viewport and layers can be changed like those of any other camera.
+pub const MAIN_CAMERA: &str = "main";
+
+/// The part of the target a camera draws into, normalized with the origin at
+/// the top left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewportRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for ViewportRect {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl From<[f32; 4]> for ViewportRect {
+    fn from([x, y, width, height]: [f32; 4]) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+impl ViewportRect {
+    /// The whole target.
+    pub const FULL: Self = Self {
+        x: 0.,
+        y: 0.,
+        width: 1.,
+        height: 1.,
+    };
+
+    /// The rect in pixels of a target of the size as `[x, y, width, height]`,
+    /// rounded to whole pixels and cut off at the edges of the target.
+    pub fn pixels(&self, target_size: PhysicalSize<u32>) -> [u32; 4] {
+        let (width, height) = (target_size.width as f32, target_size.height as f32);
+        let left = (self.x * width).round().clamp(0., width);
+        let top = (self.y * height).round().clamp(0., height);
+        let right = ((self.x + self.width) * width).round().clamp(left, width);
+        let bottom = ((self.y + self.height) * height).round().clamp(top, height);
+
+        [left, top, right - left, bottom - top].map(|pixels| pixels as u32)
+    }
+
+    /// Width over height of the rect on a target of the size.
+    pub fn aspect_ratio(&self, target_size: PhysicalSize<u32>) -> f32 {
+        (self.width * target_size.width as f32) / (self.height * target_size.height as f32)
+    }
+}
+
+/// Trauma-based screen shake. Trauma is added by events like hits and
+/// explosions and wears off over time, the camera shakes with the square of
+/// the trauma along smooth noise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraShake {
+    /// Current trauma between 0 and 1
+    pub trauma: f32,
+    /// How much trauma wears off every second
+    pub decay: f32,
+    /// Offset in world units at full trauma
+    pub max_offset: f32,
+    /// Rotation in rad at full trauma
+    pub max_rotation_rad: f32,
+    /// How fast the shake changes direction, in noise samples per second
+    pub frequency: f32,
+    /// Seconds the shake has been running for, where the noise is sampled
+    pub time: f32,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            trauma: 0.,
+            decay: 1.,
+            max_offset: 0.3,
+            max_rotation_rad: 0.05,
+            frequency: 20.,
+            time: 0.,
+        }
+    }
+}
+
+impl CameraShake {
+    /// The offset and the rotation of the camera at the current time.
+    pub fn sample(&self) -> (Vector2<f32>, f32) {
+        let shake = self.trauma.clamp(0., 1.).powi(2);
+        let time = self.time * self.frequency;
+
+        (
+            [
+                noise(0, time) * self.max_offset * shake,
+                noise(1, time) * self.max_offset * shake,
+            ]
+            .into(),
+            noise(2, time) * self.max_rotation_rad * shake,
+        )
+    }
+}
+
+/// The duration in fractional seconds.
+pub(crate) fn seconds(duration: Duration) -> f32 {
+    duration.num_microseconds().unwrap_or(i64::MAX) as f32 / 1_000_000.
+}
+
+/// Smooth value noise between -1 and 1, different for every seed.
+fn noise(seed: u32, time: f32) -> f32 {
+    let value = |lattice: i32| {
+        let mut hash = (lattice as u32).wrapping_mul(0x9E37_79B9) ^ seed.wrapping_mul(0x85EB_CA6B);
+        hash ^= hash >> 16;
+        hash = hash.wrapping_mul(0x7FEB_352D);
+        hash ^= hash >> 15;
+        hash as f32 / u32::MAX as f32 * 2. - 1.
+    };
+
+    let lattice = time.floor();
+    let fraction = time - lattice;
+    let smooth = fraction * fraction * (3. - 2. * fraction);
+    value(lattice as i32) * (1. - smooth) + value(lattice as i32 + 1) * smooth
+}
+
 impl Camera {
     pub(crate) fn raw(&self) -> CameraRaw {
         CameraRaw {
@@ -29,15 +172,119 @@ impl Camera {
         }
     }
 
+    /// The size at which every pixel of sprites with the given pixels per unit
+    /// covers exactly `zoom` by `zoom` pixels of a viewport this tall.
+    pub fn pixel_perfect_size(viewport_height: u32, pixels_per_unit: f32, zoom: u32) -> f32 {
+        viewport_height as f32 / (2. * pixels_per_unit * zoom.max(1) as f32)
+    }
+
+    /// The position the view is rendered from, shaken and then snapped.
+    pub fn snapped_position(&self) -> Vector2<f32> {
+        let (offset, _) = self.shake.sample();
+        let position = [self.position.x + offset.x, self.position.y + offset.y];
+
+        match self.pixel_snapping {
+            Some(pixels_per_unit) => position
+                .map(|coordinate| (coordinate * pixels_per_unit).round() / pixels_per_unit)
+                .into(),
+            None => position.into(),
+        }
+    }
+
+    /// Add trauma to shake the camera, it is capped at 1.
+    pub fn add_trauma(&mut self, trauma: f32) {
+        self.shake.trauma = (self.shake.trauma + trauma).clamp(0., 1.);
+    }
+
+    /// Advance the shake and wear off its trauma.
+    pub fn update_shake(&mut self, delta_time: Duration) {
+        let seconds = seconds(delta_time);
+        self.shake.time += seconds;
+        self.shake.trauma = (self.shake.trauma - self.shake.decay * seconds).max(0.);
+    }
+
+    /// Multiply the zoom by the factor, keeping the position in place.
+    pub fn zoom_by(&mut self, factor: f32) {
+        self.zoom *= factor;
+    }
+
+    /// Multiply the zoom by the factor, keeping the world position under
+    /// the anchor in place. Used to zoom towards the cursor.
+    pub fn zoom_towards(&mut self, factor: f32, anchor: impl Into<Vector2<f32>>) {
+        let anchor = anchor.into();
+        self.zoom *= factor;
+        self.position = [
+            anchor.x + (self.position.x - anchor.x) / factor,
+            anchor.y + (self.position.y - anchor.y) / factor,
+        ]
+        .into();
+    }
+
+    /// Half of the world units seen horizontally and vertically.
+    pub(crate) fn half_extents(&self) -> [f32; 2] {
+        let half_height = self.size / self.zoom;
+        [half_height * self.aspect_ratio, half_height]
+    }
+
+    /// The rotation the view is rendered with, including the shake.
+    fn view_rotation(&self) -> f32 {
+        let (_, rotation) = self.shake.sample();
+        self.rotation_rad + rotation
+    }
+
+    /// Project the world position into normalized device coordinates.
+    pub(crate) fn world_to_ndc(&self, world_position: Vector2<f32>) -> Vector2<f32> {
+        let center = self.snapped_position();
+        let [half_width, half_height] = self.half_extents();
+        let (sin, cos) = self.view_rotation().sin_cos();
+
+        // The world turns the opposite way of the camera
+        let [x, y] = [world_position.x - center.x, world_position.y - center.y];
+        [
+            (x * cos + y * sin) / half_width,
+            (y * cos - x * sin) / half_height,
+        ]
+        .into()
+    }
+
+    /// Project normalized device coordinates back into the world.
+    pub(crate) fn ndc_to_world(&self, ndc: Vector2<f32>) -> Vector2<f32> {
+        let center = self.snapped_position();
+        let [half_width, half_height] = self.half_extents();
+        let (sin, cos) = self.view_rotation().sin_cos();
+
+        let [x, y] = [ndc.x * half_width, ndc.y * half_height];
+        [center.x + x * cos - y * sin, center.y + x * sin + y * cos].into()
+    }
+
+    /// The smallest world-aligned rectangle containing everything the camera
+    /// sees, as its `[min, max]` corners.
+    pub fn visible_bounds(&self) -> [Vector2<f32>; 2] {
+        let corners = [[-1., -1.], [1., -1.], [-1., 1.], [1., 1.]]
+            .map(|corner: [f32; 2]| self.ndc_to_world(corner.into()));
+
+        let mut min = corners[0];
+        let mut max = corners[0];
+        for corner in corners {
+            min = [min.x.min(corner.x), min.y.min(corner.y)].into();
+            max = [max.x.max(corner.x), max.y.max(corner.y)].into();
+        }
+        [min, max]
+    }
+
     /// Calculate the view matrix
     pub fn view(&self) -> ColumnMatrix4<f32> {
+        let center = self.snapped_position();
+        let [half_width, half_height] = self.half_extents();
+        let (sin, cos) = self.view_rotation().sin_cos();
+
         ColumnMatrix4 {
-            x: [1. / (self.aspect_ratio * self.size), 0., 0., 0.].into(),
-            y: [0., 1. / self.size, 0., 0.].into(),
+            x: [cos / half_width, -sin / half_height, 0., 0.].into(),
+            y: [sin / half_width, cos / half_height, 0., 0.].into(),
             z: [0., 0., 1. / self.size, 0.].into(),
             w: [
-                self.position.x / self.size,
-                -self.position.y / self.size,
+                -(center.x * cos + center.y * sin) / half_width,
+                (center.x * sin - center.y * cos) / half_height,
                 0.,
                 1.,
             ]
@@ -0,0 +1,203 @@
+use chrono::Duration;
+use mint::Vector2;
+
+use crate::{camera::seconds, Camera, Renderer};
+
+/// Moves a camera after a target in the world. The target can move freely
+/// within the dead zone, beyond it the camera catches up with critically
+/// damped smoothing, looking ahead in the direction the target moves, and
+/// stays within the bounds of the level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraController {
+    target: [f32; 2],
+    /// Half the size of the area around the camera the target moves in
+    /// without the camera following
+    dead_zone: [f32; 2],
+    /// Roughly the time in seconds the camera takes to catch up
+    smooth_time: f32,
+    /// Seconds of the target's movement the camera looks ahead
+    look_ahead: f32,
+    /// `[min, max]` corners of the world the camera doesn't see past
+    bounds: Option<[[f32; 2]; 2]>,
+    /// Where the camera wants to be, the center of the dead zone
+    focus: [f32; 2],
+    position: [f32; 2],
+    velocity: [f32; 2],
+    /// The target at the last update, to know how fast it moves
+    last_target: [f32; 2],
+}
+
+impl CameraController {
+    /// A controller with the camera and its target at the position, following
+    /// the target without smoothing.
+    pub fn new(position: impl Into<Vector2<f32>>) -> Self {
+        let position = to_array(position);
+
+        Self {
+            target: position,
+            dead_zone: [0., 0.],
+            smooth_time: 0.,
+            look_ahead: 0.,
+            bounds: None,
+            focus: position,
+            position,
+            velocity: [0., 0.],
+            last_target: position,
+        }
+    }
+
+    /// Width and height of the area in the middle of the view the target
+    /// moves in without the camera following.
+    pub fn dead_zone(mut self, size: impl Into<Vector2<f32>>) -> Self {
+        self.dead_zone = to_array(size).map(|extent| extent.max(0.) / 2.);
+        self
+    }
+
+    /// Roughly the time the camera takes to catch up with the target.
+    pub fn smoothing(mut self, smooth_time: Duration) -> Self {
+        self.smooth_time = seconds(smooth_time).max(0.);
+        self
+    }
+
+    /// Look ahead by where the target will be after the duration if it keeps
+    /// its current velocity.
+    pub fn look_ahead(mut self, duration: Duration) -> Self {
+        self.look_ahead = seconds(duration).max(0.);
+        self
+    }
+
+    /// Keep the view within the world-aligned rectangle between the corners.
+    /// A level smaller than the view is centered.
+    pub fn bounds(mut self, min: impl Into<Vector2<f32>>, max: impl Into<Vector2<f32>>) -> Self {
+        self.bounds = Some([to_array(min), to_array(max)]);
+        self
+    }
+
+    /// The position the camera was moved to by the last update.
+    pub fn position(&self) -> Vector2<f32> {
+        self.position.into()
+    }
+
+    pub fn target(&self) -> Vector2<f32> {
+        self.target.into()
+    }
+
+    /// Change the position the camera follows.
+    pub fn follow(&mut self, target: impl Into<Vector2<f32>>) {
+        self.target = to_array(target);
+    }
+
+    /// Move the camera onto the target without smoothing or looking ahead,
+    /// like when the level starts or the player respawns.
+    pub fn jump_to(&mut self, target: impl Into<Vector2<f32>>) {
+        let target = to_array(target);
+        self.target = target;
+        self.last_target = target;
+        self.focus = target;
+        self.position = target;
+        self.velocity = [0., 0.];
+    }
+
+    /// Advance by the time the renderer took to draw the last frame and move
+    /// its camera.
+    pub fn update(&mut self, renderer: &mut Renderer) {
+        let delta_time = renderer.delta_time();
+        renderer.mutate_camera(|camera| self.advance(delta_time, camera));
+    }
+
+    /// Move the controller by the duration and place the camera, its view
+    /// being used to stay within the bounds.
+    pub fn advance(&mut self, delta_time: Duration, camera: &mut Camera) {
+        let delta_time = seconds(delta_time);
+        let [min, max] = self.limits(camera);
+
+        for axis in [0, 1] {
+            if delta_time > 0. {
+                let target_velocity = (self.target[axis] - self.last_target[axis]) / delta_time;
+                let goal = self.target[axis] + target_velocity * self.look_ahead;
+
+                // Drag the dead zone along so that the goal stays inside it
+                let dead_zone = self.dead_zone[axis];
+                self.focus[axis] = self.focus[axis]
+                    .clamp(goal - dead_zone, goal + dead_zone)
+                    .clamp(min[axis], max[axis]);
+
+                (self.position[axis], self.velocity[axis]) = smooth_damp(
+                    self.position[axis],
+                    self.focus[axis],
+                    self.velocity[axis],
+                    self.smooth_time,
+                    delta_time,
+                );
+            }
+
+            let clamped = self.position[axis].clamp(min[axis], max[axis]);
+            if clamped != self.position[axis] {
+                // Stop at the edge instead of pushing against it
+                self.position[axis] = clamped;
+                self.velocity[axis] = 0.;
+            }
+        }
+
+        if delta_time > 0. {
+            self.last_target = self.target;
+        }
+        camera.position = self.position.into();
+    }
+
+    /// The lowest and highest positions of the camera for its view to stay
+    /// within the bounds.
+    fn limits(&self, camera: &Camera) -> [[f32; 2]; 2] {
+        let Some([min, max]) = self.bounds else {
+            return [[f32::NEG_INFINITY; 2], [f32::INFINITY; 2]];
+        };
+
+        // The world-aligned extents of the possibly rotated view
+        let [half_width, half_height] = camera.half_extents();
+        let (sin, cos) = camera.rotation_rad.sin_cos();
+        let extents = [
+            half_width * cos.abs() + half_height * sin.abs(),
+            half_width * sin.abs() + half_height * cos.abs(),
+        ];
+
+        let mut limits = [[0.; 2]; 2];
+        for axis in [0, 1] {
+            let center = (min[axis] + max[axis]) / 2.;
+            // A level smaller than the view keeps the camera in its middle
+            let extent = extents[axis].min(center - min[axis]);
+            limits[0][axis] = min[axis] + extent;
+            limits[1][axis] = max[axis] - extent;
+        }
+        limits
+    }
+}
+
+fn to_array(position: impl Into<Vector2<f32>>) -> [f32; 2] {
+    let position = position.into();
+    [position.x, position.y]
+}
+
+/// Move towards the goal like a critically damped spring, returning the new
+/// position and velocity. See Game Programming Gems 4, chapter 1.10.
+fn smooth_damp(
+    position: f32,
+    goal: f32,
+    velocity: f32,
+    smooth_time: f32,
+    delta_time: f32,
+) -> (f32, f32) {
+    if smooth_time <= 0. {
+        return (goal, 0.);
+    }
+
+    let omega = 2. / smooth_time;
+    let x = omega * delta_time;
+    let decay = 1. / (1. + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = position - goal;
+    let temp = (velocity + omega * change) * delta_time;
+
+    (
+        goal + (change + temp) * decay,
+        (velocity - omega * temp) * decay,
+    )
+}
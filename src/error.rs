@@ -0,0 +1,167 @@
+use std::fmt::Display;
+
+use crate::{SpriteIndex, SpriteRect};
+
+/// Everything that can go wrong while setting up or drawing with a renderer.
+#[derive(Debug)]
+pub enum TangerineError {
+    /// The window to present onto could not be built.
+    WindowCreation(winit::error::OsError),
+    /// The surface of the window could not be created.
+    SurfaceCreation(wgpu::CreateSurfaceError),
+    /// None of the adapters on the machine can draw onto the target.
+    NoCompatibleAdapter,
+    /// The adapter refused to create a device with the requested limits.
+    DeviceRequest(wgpu::RequestDeviceError),
+    /// The target's format doesn't support the requested MSAA sample count.
+    UnsupportedSampleCount(u32),
+    /// A file could not be read or written.
+    Io(std::io::Error),
+    /// An image could not be encoded or decoded.
+    Image(image::ImageError),
+    /// The manifest of a saved atlas is malformed.
+    AtlasManifest(String),
+    /// A sprite sheet exported by another tool could not be parsed.
+    InvalidSheet(serde_json::Error),
+    /// An exported sprite sheet was parsed but doesn't make sense, like a
+    /// frame outside of the image.
+    MalformedSheet(String),
+    /// A slice of a sprite sheet is empty or reaches outside of the image.
+    InvalidSlice {
+        slice: SpriteRect,
+        sheet_size: [u32; 2],
+    },
+    /// A font could not be parsed.
+    InvalidFont(String),
+    /// A map made in Tiled could not be parsed.
+    InvalidMap(String),
+    /// The frame could not be drawn onto the surface.
+    Surface(wgpu::SurfaceError),
+    /// A drawn frame could not be read back from the GPU.
+    BufferMap(wgpu::BufferAsyncError),
+    /// A sprite index that is not in the atlas was drawn.
+    UnknownSprite(SpriteIndex),
+    /// A sprite without any pixels was added to the atlas.
+    EmptySprite(SpriteIndex),
+    /// A sprite with its padding doesn't fit into the largest atlas page.
+    SpriteTooLarge {
+        sprite: SpriteIndex,
+        width: u32,
+        height: u32,
+        max_size: u32,
+    },
+    /// An animation without any frames was played.
+    EmptyAnimation,
+    /// No camera with the name was added to the renderer.
+    UnknownCamera(String),
+}
+
+impl Display for TangerineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TangerineError::WindowCreation(err) => write!(f, "failed to create a window: {err}"),
+            TangerineError::SurfaceCreation(err) => {
+                write!(f, "failed to create a surface: {err}")
+            }
+            TangerineError::NoCompatibleAdapter => write!(f, "no compatible adapter was found"),
+            TangerineError::DeviceRequest(err) => write!(f, "failed to request a device: {err}"),
+            TangerineError::UnsupportedSampleCount(count) => {
+                write!(f, "{count} samples per pixel are not supported")
+            }
+            TangerineError::Io(err) => write!(f, "failed to access a file: {err}"),
+            TangerineError::Image(err) => write!(f, "invalid image: {err}"),
+            TangerineError::AtlasManifest(reason) => write!(f, "invalid atlas manifest: {reason}"),
+            TangerineError::InvalidSheet(err) => write!(f, "invalid sprite sheet: {err}"),
+            TangerineError::MalformedSheet(reason) => {
+                write!(f, "malformed sprite sheet: {reason}")
+            }
+            TangerineError::InvalidSlice {
+                slice,
+                sheet_size: [width, height],
+            } => write!(
+                f,
+                "slice {slice:?} is empty or outside of the {width}x{height} sprite sheet"
+            ),
+            TangerineError::InvalidFont(reason) => write!(f, "invalid font: {reason}"),
+            TangerineError::InvalidMap(reason) => write!(f, "invalid map: {reason}"),
+            TangerineError::Surface(err) => write!(f, "failed to draw onto the surface: {err}"),
+            TangerineError::BufferMap(err) => write!(f, "failed to read the frame back: {err}"),
+            TangerineError::UnknownSprite(idx) => write!(f, "sprite {idx} is not in the atlas"),
+            TangerineError::EmptySprite(idx) => write!(f, "sprite {idx} has no pixels"),
+            TangerineError::SpriteTooLarge {
+                sprite,
+                width,
+                height,
+                max_size,
+            } => write!(
+                f,
+                "sprite {sprite} ({width}x{height} with padding) doesn't fit into the maximum texture size of {max_size}"
+            ),
+            TangerineError::EmptyAnimation => write!(f, "the animation has no frames"),
+            TangerineError::UnknownCamera(name) => write!(f, "there is no camera named {name}"),
+        }
+    }
+}
+
+impl std::error::Error for TangerineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TangerineError::WindowCreation(err) => Some(err),
+            TangerineError::SurfaceCreation(err) => Some(err),
+            TangerineError::NoCompatibleAdapter => None,
+            TangerineError::DeviceRequest(err) => Some(err),
+            TangerineError::UnsupportedSampleCount(_) => None,
+            TangerineError::Io(err) => Some(err),
+            TangerineError::Image(err) => Some(err),
+            TangerineError::AtlasManifest(_) => None,
+            TangerineError::InvalidSheet(err) => Some(err),
+            TangerineError::MalformedSheet(_) => None,
+            TangerineError::InvalidSlice { .. } => None,
+            TangerineError::InvalidFont(_) => None,
+            TangerineError::InvalidMap(_) => None,
+            TangerineError::Surface(err) => Some(err),
+            TangerineError::BufferMap(err) => Some(err),
+            TangerineError::UnknownSprite(_) => None,
+            TangerineError::EmptySprite(_) => None,
+            TangerineError::SpriteTooLarge { .. } => None,
+            TangerineError::EmptyAnimation => None,
+            TangerineError::UnknownCamera(_) => None,
+        }
+    }
+}
+
+impl From<winit::error::OsError> for TangerineError {
+    fn from(value: winit::error::OsError) -> Self {
+        Self::WindowCreation(value)
+    }
+}
+
+impl From<wgpu::CreateSurfaceError> for TangerineError {
+    fn from(value: wgpu::CreateSurfaceError) -> Self {
+        Self::SurfaceCreation(value)
+    }
+}
+
+impl From<wgpu::RequestDeviceError> for TangerineError {
+    fn from(value: wgpu::RequestDeviceError) -> Self {
+        Self::DeviceRequest(value)
+    }
+}
+
+impl From<std::io::Error> for TangerineError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<image::ImageError> for TangerineError {
+    fn from(value: image::ImageError) -> Self {
+        Self::Image(value)
+    }
+}
+
+impl From<wgpu::SurfaceError> for TangerineError {
+    fn from(value: wgpu::SurfaceError) -> Self {
+        Self::Surface(value)
+    }
+}
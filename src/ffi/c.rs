@@ -1,10 +1,10 @@
 use std::{
     alloc::Layout,
     convert::Infallible,
-    ffi::{c_char, c_uchar, CStr},
+    ffi::{c_char, c_float, c_uchar, c_uint, CStr},
 };
 
-use crate::{StandaloneInputState, StandaloneRenderer};
+use crate::{RendererBuilder, StandaloneInputState, StandaloneRenderer, VSync};
 
 #[no_mangle]
 unsafe extern "C" fn tangerine_new() -> *mut StandaloneRenderer {
@@ -13,6 +13,141 @@ unsafe extern "C" fn tangerine_new() -> *mut StandaloneRenderer {
     ptr
 }
 
+#[no_mangle]
+unsafe extern "C" fn tangerine_builder_new() -> *mut RendererBuilder {
+    let ptr = std::alloc::alloc(Layout::new::<RendererBuilder>()) as *mut RendererBuilder;
+    std::ptr::write(ptr, RendererBuilder::default());
+    ptr
+}
+
+#[no_mangle]
+unsafe extern "C" fn tangerine_builder_set_backends(
+    builder: *mut RendererBuilder,
+    backends: c_uint,
+) {
+    (&mut *builder).backends = wgpu::Backends::from_bits_truncate(backends);
+}
+
+#[no_mangle]
+unsafe extern "C" fn tangerine_builder_set_high_performance(
+    builder: *mut RendererBuilder,
+    high_performance: bool,
+) {
+    (&mut *builder).power_preference = if high_performance {
+        wgpu::PowerPreference::HighPerformance
+    } else {
+        wgpu::PowerPreference::LowPower
+    };
+}
+
+/// Returns false and keeps the previous name if the name isn't valid UTF-8.
+#[no_mangle]
+unsafe extern "C" fn tangerine_builder_set_adapter_name(
+    builder: *mut RendererBuilder,
+    name: *const c_char,
+) -> bool {
+    if name.is_null() {
+        (&mut *builder).adapter_name = None;
+        return true;
+    }
+
+    match CStr::from_ptr(name).to_str() {
+        Ok(name) => {
+            (&mut *builder).adapter_name = Some(name.to_owned());
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn tangerine_builder_set_vsync(builder: *mut RendererBuilder, vsync: c_uint) {
+    (&mut *builder).vsync = match vsync {
+        1 => VSync::Off,
+        2 => VSync::Mailbox,
+        _ => VSync::On,
+    };
+}
+
+#[no_mangle]
+unsafe extern "C" fn tangerine_builder_set_sample_count(
+    builder: *mut RendererBuilder,
+    sample_count: c_uint,
+) {
+    (&mut *builder).sample_count = sample_count.max(1);
+}
+
+#[no_mangle]
+unsafe extern "C" fn tangerine_builder_set_clear_color(
+    builder: *mut RendererBuilder,
+    color_rgb: *mut c_uchar,
+) {
+    (&mut *builder).clear_color = if !color_rgb.is_null() {
+        Some(
+            [
+                *color_rgb.offset(0),
+                *color_rgb.offset(1),
+                *color_rgb.offset(2),
+            ]
+            .into(),
+        )
+    } else {
+        None
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn tangerine_builder_set_features(builder: *mut RendererBuilder, features: u64) {
+    (&mut *builder).features = wgpu::Features::from_bits_truncate(features);
+}
+
+#[no_mangle]
+unsafe extern "C" fn tangerine_builder_set_limits(
+    builder: *mut RendererBuilder,
+    limits: c_uint,
+    max_texture_dimension_2d: c_uint,
+) {
+    let mut limits = match limits {
+        1 => wgpu::Limits::downlevel_defaults(),
+        2 => wgpu::Limits::downlevel_webgl2_defaults(),
+        _ => wgpu::Limits::default(),
+    };
+    if max_texture_dimension_2d > 0 {
+        limits.max_texture_dimension_2d = max_texture_dimension_2d;
+    }
+    (&mut *builder).limits = Some(limits);
+}
+
+#[no_mangle]
+unsafe extern "C" fn tangerine_builder_set_pixels_per_unit(
+    builder: *mut RendererBuilder,
+    pixels_per_unit: c_float,
+) {
+    (&mut *builder).pixels_per_unit = (pixels_per_unit > 0.).then_some(pixels_per_unit);
+}
+
+/// Consumes the builder, returns null if the renderer could not be created.
+#[no_mangle]
+unsafe extern "C" fn tangerine_new_with_builder(
+    builder: *mut RendererBuilder,
+) -> *mut StandaloneRenderer {
+    let renderer_builder = std::ptr::read(builder);
+    std::alloc::dealloc(builder as _, Layout::new::<RendererBuilder>());
+
+    match StandaloneRenderer::try_with_builder("Hello, world!", renderer_builder) {
+        Ok(renderer) => {
+            let ptr =
+                std::alloc::alloc(Layout::new::<StandaloneRenderer>()) as *mut StandaloneRenderer;
+            std::ptr::write(ptr, renderer);
+            ptr
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            std::ptr::null_mut()
+        }
+    }
+}
+
 #[no_mangle]
 unsafe extern "C" fn tangerine_delete(renderer: *mut StandaloneRenderer) {
     std::alloc::dealloc(renderer as _, Layout::new::<StandaloneRenderer>());
@@ -21,7 +156,9 @@ unsafe extern "C" fn tangerine_delete(renderer: *mut StandaloneRenderer) {
 #[no_mangle]
 unsafe extern "C" fn tangerine_set_title(renderer: *mut StandaloneRenderer, title: *const c_char) {
     let c_str = CStr::from_ptr(title).to_str().unwrap();
-    (&mut *renderer).window.set_title(c_str);
+    if let Some(window) = (*renderer).window() {
+        window.set_title(c_str);
+    }
 }
 
 #[no_mangle]
@@ -43,13 +180,29 @@ unsafe extern "C" fn tangerine_set_background_color(
     }
 }
 
+/// The part of the input state C can read, `tangerine_input` in the header.
+#[repr(C)]
+struct TangerineInput {
+    cursor_position_x: c_uint,
+    cursor_position_y: c_uint,
+}
+
+impl From<&StandaloneInputState> for TangerineInput {
+    fn from(input: &StandaloneInputState) -> Self {
+        Self {
+            cursor_position_x: input.cursor_pos.x,
+            cursor_position_y: input.cursor_pos.y,
+        }
+    }
+}
+
 #[no_mangle]
 unsafe extern "C" fn tangerine_run(
     renderer: *mut StandaloneRenderer,
-    callback: extern "C" fn(*mut StandaloneRenderer, StandaloneInputState) -> (),
+    callback: extern "C" fn(*mut StandaloneRenderer, TangerineInput) -> (),
 ) {
     let _ = std::ptr::read(renderer).run::<Infallible>(move |_, input| {
-        callback(renderer, input);
+        callback(renderer, input.into());
         Ok(())
     });
 }
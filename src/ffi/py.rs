@@ -1,13 +1,21 @@
+// The impls generated by `#[pymethods]` of this pyo3 version trip the lint
+#![allow(non_local_definitions)]
+
 use std::cell::OnceCell;
 
 use cint::EncodedSrgb;
 use image::DynamicImage;
 use mint::{Vector2, Vector3};
-use pyo3::{buffer::PyBuffer, prelude::*, types::PyTuple};
+use pyo3::{
+    buffer::PyBuffer,
+    exceptions::{PyKeyError, PyRuntimeError, PyValueError},
+    prelude::*,
+    types::PyTuple,
+};
 
 use crate::{
-    LayerIdentifier, SpriteIndex, SpriteInstance, SpriteTransform, StandaloneInputState,
-    StandaloneRenderer,
+    LayerIdentifier, RendererBuilder, SpriteIndex, SpriteInstance, SpriteTransform,
+    StandaloneInputState, StandaloneRenderer, VSync,
 };
 
 /// Input transferred to the Python's world.
@@ -41,29 +49,149 @@ impl PythonInput {
     }
 }
 
+/// A sprite referred to from Python either by its index or by its name.
+#[derive(Debug, Clone, FromPyObject)]
+enum PythonSprite {
+    Index(SpriteIndex),
+    Named(String),
+}
+
 /// Adapter of the [`StandaloneRenderer`] to interact with Python's world.
 #[pyclass(name = "Renderer", unsendable)]
 pub struct PythonRenderer {
     new_background_color: OnceCell<Option<EncodedSrgb>>,
     new_title: OnceCell<String>,
 
-    sprites_to_add: Vec<DynamicImage>,
-    to_draw_list: Vec<(SpriteIndex, Option<LayerIdentifier>, SpriteInstance)>,
+    sprites_to_add: Vec<(Option<String>, DynamicImage)>,
+    to_draw_list: Vec<(PythonSprite, Option<LayerIdentifier>, SpriteInstance)>,
 
     last_sprite_index: SpriteIndex,
+
+    builder: RendererBuilder,
 }
 
 #[pymethods]
 impl PythonRenderer {
     #[new]
-    pub fn new(_py: Python<'_>) -> Self {
-        Self {
+    #[pyo3(signature = (
+        *,
+        vsync = None,
+        sample_count = None,
+        adapter = None,
+        high_performance = None,
+        backends = None,
+        features = None,
+        limits = None,
+        max_texture_dimension = None,
+        clear_color = None,
+        pixels_per_unit = None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        py: Python<'_>,
+        vsync: Option<&str>,
+        sample_count: Option<u32>,
+        adapter: Option<String>,
+        high_performance: Option<bool>,
+        backends: Option<Vec<String>>,
+        features: Option<u64>,
+        limits: Option<&str>,
+        max_texture_dimension: Option<u32>,
+        clear_color: Option<PyObject>,
+        pixels_per_unit: Option<f32>,
+    ) -> PyResult<Self> {
+        let mut builder = RendererBuilder::default();
+
+        if let Some(backends) = backends {
+            let mut bits = wgpu::Backends::empty();
+            for backend in backends {
+                bits |= match backend.as_str() {
+                    "vulkan" => wgpu::Backends::VULKAN,
+                    "gl" => wgpu::Backends::GL,
+                    "metal" => wgpu::Backends::METAL,
+                    "dx12" => wgpu::Backends::DX12,
+                    "dx11" => wgpu::Backends::DX11,
+                    "browser_webgpu" => wgpu::Backends::BROWSER_WEBGPU,
+                    _ => {
+                        return Err(PyValueError::new_err(format!(
+                            "unknown backend {backend:?}"
+                        )))
+                    }
+                };
+            }
+            builder = builder.backends(bits);
+        }
+
+        if let Some(features) = features {
+            builder = builder.features(wgpu::Features::from_bits_truncate(features));
+        }
+
+        if limits.is_some() || max_texture_dimension.is_some() {
+            let mut wgpu_limits = match limits.unwrap_or("default") {
+                "default" => wgpu::Limits::default(),
+                "downlevel" => wgpu::Limits::downlevel_defaults(),
+                "webgl2" => wgpu::Limits::downlevel_webgl2_defaults(),
+                _ => {
+                    return Err(PyValueError::new_err(
+                        "limits must be one of \"default\", \"downlevel\" or \"webgl2\"",
+                    ))
+                }
+            };
+            if let Some(max_texture_dimension) = max_texture_dimension {
+                wgpu_limits.max_texture_dimension_2d = max_texture_dimension;
+            }
+            builder = builder.limits(wgpu_limits);
+        }
+
+        if let Some(color) = clear_color {
+            let color = match color.extract::<[u8; 3]>(py) {
+                Ok(arr) => arr,
+                Err(_) => color.extract::<[f32; 3]>(py)?.map(|x| (x * 255.) as u8),
+            };
+            builder = builder.clear_color(Some(color));
+        }
+
+        if let Some(pixels_per_unit) = pixels_per_unit {
+            builder = builder.pixels_per_unit(pixels_per_unit);
+        }
+
+        if let Some(vsync) = vsync {
+            builder = builder.vsync(match vsync {
+                "on" => VSync::On,
+                "off" => VSync::Off,
+                "mailbox" => VSync::Mailbox,
+                _ => {
+                    return Err(PyValueError::new_err(
+                        "vsync must be one of \"on\", \"off\" or \"mailbox\"",
+                    ))
+                }
+            });
+        }
+
+        if let Some(sample_count) = sample_count {
+            builder = builder.sample_count(sample_count);
+        }
+
+        if let Some(adapter) = adapter {
+            builder = builder.adapter_name(adapter);
+        }
+
+        if let Some(high_performance) = high_performance {
+            builder = builder.power_preference(if high_performance {
+                wgpu::PowerPreference::HighPerformance
+            } else {
+                wgpu::PowerPreference::LowPower
+            });
+        }
+
+        Ok(Self {
             new_background_color: OnceCell::new(),
             new_title: OnceCell::new(),
             sprites_to_add: vec![],
             to_draw_list: vec![],
             last_sprite_index: 0,
-        }
+            builder,
+        })
     }
 
     /// Hijack the main loop with the callback to call when a redraw is requested.
@@ -72,59 +200,74 @@ impl PythonRenderer {
         py: Python<'py>,
         redraw_callback: PyObject,
     ) -> PyResult<()> {
-        StandaloneRenderer::new("Hello, Python!").run({
-            let gil_pool = unsafe { py.new_pool() };
-            let self_py = slf.into_py(py);
+        let builder = slf.builder.clone();
+        StandaloneRenderer::try_with_builder("Hello, Python!", builder)
+            .map_err(|err| PyRuntimeError::new_err(err.to_string()))?
+            .run_with_renderer({
+                let gil_pool = unsafe { py.new_pool() };
+                let self_py = slf.into_py(py);
 
-            move |renderer, gathered_input| {
-                let python = gil_pool.python();
-                let self_py: Py<Self> = self_py.extract::<Py<Self>>(python)?;
-                let mut slf = self_py.borrow_mut(python);
+                move |renderer, frame_builder, gathered_input| {
+                    let python = gil_pool.python();
+                    let self_py: Py<Self> = self_py.extract::<Py<Self>>(python)?;
+                    let mut slf = self_py.borrow_mut(python);
 
-                if let Some(color) = slf.new_background_color.take() {
-                    renderer.clear_color = color;
-                }
+                    if let Some(color) = slf.new_background_color.take() {
+                        renderer.clear_color = color;
+                    }
 
-                if let Some(title) = slf.new_title.take() {
-                    renderer.window.set_title(&title);
-                }
+                    if let Some(title) = slf.new_title.take() {
+                        if let Some(window) = renderer.window() {
+                            window.set_title(&title);
+                        }
+                    }
 
-                let cursor_pos_world_space = renderer.window_to_world(gathered_input.cursor_pos);
-
-                redraw_callback.call1(
-                    python,
-                    (
-                        slf.into_py(python),
-                        PythonInput {
-                            gathered_input,
-                            cursor_pos_world_space,
-                        },
-                    ),
-                )?;
-
-                let mut self_borrow = self_py.borrow_mut(python);
-                if !self_borrow.sprites_to_add.is_empty() {
-                    let _ = std::mem::take(&mut self_borrow.sprites_to_add)
-                        .into_iter()
-                        .fold(renderer.atlas(), |r, i| r.add_sprite_dynamically(i).0)
-                        .finalize_and_repack();
-                }
+                    let cursor_pos_world_space = frame_builder
+                        .viewport()
+                        .window_to_world(gathered_input.cursor_pos);
 
-                let mut frame_builder = renderer.begin_frame();
+                    redraw_callback.call1(
+                        python,
+                        (
+                            slf.into_py(python),
+                            PythonInput {
+                                gathered_input: gathered_input.clone(),
+                                cursor_pos_world_space,
+                            },
+                        ),
+                    )?;
 
-                let draw_list = &mut self_borrow.to_draw_list;
-                for (draw_idx, layer, instance) in draw_list.iter() {
-                    frame_builder = frame_builder.draw_sprite(
-                        *draw_idx,
-                        layer.to_owned().unwrap_or_default(),
-                        *instance,
-                    )
-                }
-                draw_list.clear();
+                    let mut self_borrow = self_py.borrow_mut(python);
+                    if !self_borrow.sprites_to_add.is_empty() {
+                        let _ = std::mem::take(&mut self_borrow.sprites_to_add)
+                            .into_iter()
+                            .fold(renderer.atlas(), |atlas, (name, image)| {
+                                let (atlas, idx) = atlas.add_sprite_dynamically(image);
+                                match name {
+                                    Some(name) => atlas.name_sprite(idx, name),
+                                    None => atlas,
+                                }
+                            })
+                            .finalize_and_repack();
+                    }
 
-                Ok(frame_builder)
-            }
-        })
+                    let draw_list = &mut self_borrow.to_draw_list;
+                    for (sprite, layer, instance) in draw_list.iter() {
+                        let draw_idx = match sprite {
+                            PythonSprite::Index(idx) => *idx,
+                            PythonSprite::Named(name) => {
+                                renderer.sprite(name).ok_or_else(|| {
+                                    PyKeyError::new_err(format!("no sprite is named {name:?}"))
+                                })?
+                            }
+                        };
+                        frame_builder.submit_sprite(draw_idx, layer.clone(), *instance);
+                    }
+                    draw_list.clear();
+
+                    Ok(())
+                }
+            })
     }
 
     /// Background color used for clearing the screen.
@@ -155,8 +298,15 @@ impl PythonRenderer {
         self.new_title = OnceCell::from(title.to_string())
     }
 
-    /// Add a new sprite to the list of sprites for further drawing
-    fn add_sprite(&mut self, py: Python<'_>, buffer: PyBuffer<u8>) -> SpriteIndex {
+    /// Add a new sprite, optionally under a name it can also be drawn with,
+    /// the sprite is loaded into the atlas before the next frame
+    #[pyo3(signature = (buffer, *, name = None))]
+    fn add_sprite(
+        &mut self,
+        py: Python<'_>,
+        buffer: PyBuffer<u8>,
+        name: Option<String>,
+    ) -> SpriteIndex {
         let buffer = buffer
             .as_slice(py)
             .unwrap()
@@ -166,7 +316,7 @@ impl PythonRenderer {
         let img = image::load_from_memory(&buffer).unwrap();
         let new_idx = self.last_sprite_index;
         self.last_sprite_index += 1;
-        self.sprites_to_add.push(img);
+        self.sprites_to_add.push((name, img));
         new_idx
     }
 
@@ -175,7 +325,7 @@ impl PythonRenderer {
     fn draw(
         &mut self,
         py: Python<'_>,
-        index: SpriteIndex,
+        sprite: PythonSprite,
         position: PyObject,
 
         layer: Option<LayerIdentifier>,
@@ -209,16 +359,17 @@ impl PythonRenderer {
         };
 
         self.to_draw_list.push((
-            index,
+            sprite,
             layer,
             SpriteInstance {
                 position: Vector3::from(position),
                 transform: SpriteTransform {
                     scale: scale.into(),
-                    rotation_deg: angle.unwrap_or(0.),
+                    rotation_rad: angle.unwrap_or(0.).to_radians(),
                 },
                 color: color.into(),
                 opacity: opacity.unwrap_or(1.).clamp(0., 1.),
+                ..Default::default()
             },
         ));
         Ok(())
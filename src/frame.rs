@@ -3,7 +3,10 @@ use std::alloc::{Allocator, Global};
 use cint::EncodedSrgb;
 use mint::{Vector2, Vector3};
 
-use crate::{LayerIdentifier, SpriteIndex, SpriteInstance, ViewportProperties};
+use crate::{
+    tilemap::TilemapRenderData, vertex::ShapeVertex, AnimationPlayer, LayerIdentifier, NineSlice,
+    NineSliceFill, ShapeRenderData, SpriteIndex, SpriteInstance, ViewportProperties,
+};
 
 pub struct SpriteRenderData {
     pub index: SpriteIndex,
@@ -13,11 +16,16 @@ pub struct SpriteRenderData {
 
 pub struct FrameData<A: Allocator> {
     pub sprite_data: Vec<SpriteRenderData, A>,
-    flushed_index: usize,
+    pub shape_data: Vec<ShapeRenderData>,
+    pub(crate) shape_vertices: Vec<ShapeVertex>,
+    pub(crate) tilemap_data: Vec<TilemapRenderData>,
 }
 
 pub struct FrameBuilder<A: Allocator = Global> {
     render_data: Vec<SpriteRenderData, A>,
+    shape_data: Vec<ShapeRenderData>,
+    shape_vertices: Vec<ShapeVertex>,
+    tilemap_data: Vec<TilemapRenderData>,
     viewport: ViewportProperties,
 }
 
@@ -25,6 +33,9 @@ impl FrameBuilder<Global> {
     pub fn new_global(viewport: ViewportProperties) -> Self {
         Self {
             render_data: vec![],
+            shape_data: vec![],
+            shape_vertices: vec![],
+            tilemap_data: vec![],
             viewport,
         }
     }
@@ -34,6 +45,9 @@ impl<A: Allocator> FrameBuilder<A> {
     pub fn new(viewport: ViewportProperties, allocator: A) -> Self {
         Self {
             render_data: Vec::new_in(allocator),
+            shape_data: vec![],
+            shape_vertices: vec![],
+            tilemap_data: vec![],
             viewport,
         }
     }
@@ -55,10 +69,29 @@ impl<A: Allocator> FrameBuilder<A> {
         })
     }
 
+    pub(crate) fn submit_shape(
+        &mut self,
+        layer: Option<LayerIdentifier>,
+        vertices: impl IntoIterator<Item = ShapeVertex>,
+    ) {
+        let first = self.shape_vertices.len() as u32;
+        self.shape_vertices.extend(vertices);
+        self.shape_data.push(ShapeRenderData {
+            layer,
+            vertices: first..self.shape_vertices.len() as u32,
+        });
+    }
+
+    pub(crate) fn submit_tilemap_chunk(&mut self, chunk: TilemapRenderData) {
+        self.tilemap_data.push(chunk);
+    }
+
     pub fn finalize(self) -> FrameData<A> {
         FrameData {
             sprite_data: self.render_data,
-            flushed_index: 0,
+            shape_data: self.shape_data,
+            shape_vertices: self.shape_vertices,
+            tilemap_data: self.tilemap_data,
         }
     }
 
@@ -70,6 +103,11 @@ impl<A: Allocator> FrameBuilder<A> {
             sprite_instance: Default::default(),
         }
     }
+
+    /// Draw the current frame of the animation.
+    pub fn draw_animation<'me>(&'me mut self, player: &AnimationPlayer) -> DrawSprite<'me, A> {
+        self.draw_sprite(player.sprite())
+    }
 }
 
 #[must_use]
@@ -114,6 +152,42 @@ impl<'builder, A: Allocator> DrawSprite<'builder, A> {
         self
     }
 
+    /// Mirror the sprite horizontally and/or vertically.
+    pub fn flip(mut self, x: bool, y: bool) -> Self {
+        self.sprite_instance.flip_x = x;
+        self.sprite_instance.flip_y = y;
+        self
+    }
+
+    /// Only draw a part of the sprite, given as normalized `[x, y, width, height]`.
+    pub fn sub_rect(mut self, rect: impl Into<[f32; 4]>) -> Self {
+        self.sprite_instance.sub_rect = Some(rect.into());
+        self
+    }
+
+    /// Position, rotate and scale the sprite around this point instead of the
+    /// pivot it was loaded with, normalized with the origin at the top left.
+    pub fn pivot(mut self, pivot: impl Into<[f32; 2]>) -> Self {
+        self.sprite_instance.pivot = Some(pivot.into());
+        self
+    }
+
+    /// Draw a sprite with nine-slice borders at the size in world units. The
+    /// corners keep their size while the edges and the centre fill the rest.
+    pub fn nine_slice(mut self, size: impl Into<Vector2<f32>>, fill: NineSliceFill) -> Self {
+        self.sprite_instance.nine_slice = Some(NineSlice {
+            size: size.into(),
+            fill,
+        });
+        self
+    }
+
+    /// Draw the current frame of the animation instead of the sprite.
+    pub fn animation(mut self, player: &AnimationPlayer) -> Self {
+        self.sprite_idx = player.sprite();
+        self
+    }
+
     pub fn rotate(mut self, rad: f32) -> Self {
         self.sprite_instance.transform.rotation_rad = rad;
         self
@@ -0,0 +1,386 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use chrono::Duration;
+use hashbrown::HashMap;
+use image::{imageops, DynamicImage, RgbaImage};
+use serde::{de, Deserialize, Deserializer};
+
+use crate::{
+    Animation, AnimationFrame, AnimationMode, AtlasBuilder, SpriteIndex, SpriteLoadOptions,
+    SpriteRect, TangerineError,
+};
+
+/// Duration of the frames that don't specify one, the default of Aseprite.
+const DEFAULT_FRAME_DURATION_MS: i64 = 100;
+
+/// A sprite sheet described by the JSON exported from Aseprite or from
+/// TexturePacker, in either the hash or the array format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedSheet {
+    /// Path to the sheet's image, relative to the JSON file when loaded with `.load(..)`
+    pub image: Option<PathBuf>,
+    /// The frames in the order they are listed in
+    pub frames: Vec<ImportedFrame>,
+    pub tags: Vec<ImportedTag>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedFrame {
+    /// The file name of the frame, used as the name of its sprite
+    pub name: String,
+    /// Where the frame is in the sheet, the size is the one before rotating
+    pub rect: SpriteRect,
+    /// Whether the frame is stored rotated by 90 degrees clockwise, taking
+    /// the width of the rect vertically and the height horizontally
+    pub rotated: bool,
+    /// Size of the frame before the transparent edges were trimmed
+    pub source_size: [u32; 2],
+    /// Where the trimmed pixels are in the untrimmed frame
+    pub trimmed_offset: [u32; 2],
+    /// Normalized pivot of the frame, if the exporter specifies one
+    pub pivot: Option<[f32; 2]>,
+    pub duration: Option<Duration>,
+}
+
+/// A named range of frames, Aseprite's frame tags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedTag {
+    pub name: String,
+    /// Index of the first frame of the tag
+    pub from: usize,
+    /// Index of the last frame of the tag, inclusive
+    pub to: usize,
+    pub mode: AnimationMode,
+    /// Whether the frames are played from the last one
+    pub reverse: bool,
+}
+
+/// Sprites added from an imported sheet.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ImportedSprites {
+    /// The sprite of every frame, in the order of the frames
+    pub sprites: Vec<SpriteIndex>,
+    /// An animation for every tag of the sheet
+    pub animations: HashMap<String, Animation>,
+}
+
+impl ImportedSheet {
+    /// Parse the sheet, the format is detected from the contents.
+    pub fn from_json(json: &str) -> Result<Self, TangerineError> {
+        let sheet: JsonSheet = serde_json::from_str(json).map_err(TangerineError::InvalidSheet)?;
+
+        let frames = match sheet.frames {
+            JsonFrames::Array(frames) => frames
+                .into_iter()
+                .map(|frame| (frame.filename.clone().unwrap_or_default(), frame))
+                .collect(),
+            JsonFrames::Hash(OrderedFrames(frames)) => frames,
+        };
+
+        let frame_count = frames.len();
+        let frames = frames
+            .into_iter()
+            .map(|(name, frame)| ImportedFrame {
+                name,
+                rect: frame.frame.into(),
+                rotated: frame.rotated,
+                source_size: frame
+                    .source_size
+                    .map_or([frame.frame.w, frame.frame.h], |size| [size.w, size.h]),
+                trimmed_offset: frame
+                    .sprite_source_size
+                    .map_or([0, 0], |rect| [rect.x, rect.y]),
+                pivot: frame.pivot.map(|pivot| [pivot.x, pivot.y]),
+                duration: frame.duration.map(Duration::milliseconds),
+            })
+            .collect();
+
+        let tags = sheet
+            .meta
+            .frame_tags
+            .into_iter()
+            .map(|tag| {
+                if tag.from > tag.to || tag.to >= frame_count {
+                    return Err(TangerineError::MalformedSheet(format!(
+                        "tag {:?} refers to frames outside of the sheet",
+                        tag.name
+                    )));
+                }
+
+                let (mode, reverse) = match tag.direction.as_str() {
+                    "forward" => (AnimationMode::Loop, false),
+                    "reverse" => (AnimationMode::Loop, true),
+                    "pingpong" => (AnimationMode::PingPong, false),
+                    "pingpong_reverse" => (AnimationMode::PingPong, true),
+                    direction => {
+                        return Err(TangerineError::MalformedSheet(format!(
+                            "unknown direction {direction:?} of tag {:?}",
+                            tag.name
+                        )))
+                    }
+                };
+
+                Ok(ImportedTag {
+                    name: tag.name,
+                    from: tag.from,
+                    to: tag.to,
+                    mode,
+                    reverse,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            image: sheet.meta.image.map(PathBuf::from),
+            frames,
+            tags,
+        })
+    }
+
+    /// Read and parse the sheet, resolving its image relative to the file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TangerineError> {
+        let path = path.as_ref();
+        let mut sheet = Self::from_json(&std::fs::read_to_string(path)?)?;
+        if let (Some(image), Some(directory)) = (&mut sheet.image, path.parent()) {
+            *image = directory.join(&*image);
+        }
+        Ok(sheet)
+    }
+
+    /// Open the image the sheet refers to.
+    pub fn open_image(&self) -> Result<DynamicImage, TangerineError> {
+        let Some(path) = &self.image else {
+            return Err(TangerineError::MalformedSheet(
+                "the sheet doesn't refer to an image".into(),
+            ));
+        };
+        Ok(image::open(path)?)
+    }
+}
+
+impl<'me, const N: usize> AtlasBuilder<'me, N> {
+    /// Add every frame of the imported sheet as a dynamically dispatched
+    /// sprite registered under the frame's name. Trimmed frames get their
+    /// transparent edges back, so that they line up with each other, and
+    /// the pivots of the frames replace the one of the options. Fails if a
+    /// frame reaches outside of the image.
+    pub fn add_imported_sheet(
+        self,
+        image: impl Into<DynamicImage>,
+        sheet: &ImportedSheet,
+    ) -> Result<(Self, ImportedSprites), TangerineError> {
+        let options = self.default_options;
+        self.add_imported_sheet_advanced(image, sheet, options)
+    }
+
+    /// Add the frames of the imported sheet with options not activated by default.
+    pub fn add_imported_sheet_advanced(
+        mut self,
+        image: impl Into<DynamicImage>,
+        sheet: &ImportedSheet,
+        options: impl Into<SpriteLoadOptions>,
+    ) -> Result<(Self, ImportedSprites), TangerineError> {
+        let options = options.into();
+        let image = crate::atlas::load_image(image.into(), &options);
+
+        // Rotated frames are stored turned clockwise
+        let stored_size = |frame: &ImportedFrame| match frame.rotated {
+            true => (frame.rect.height, frame.rect.width),
+            false => (frame.rect.width, frame.rect.height),
+        };
+        // Checked up front to not queue a part of the sheet
+        for frame in &sheet.frames {
+            let [source_width, source_height] = frame.source_size;
+            if frame.rect.width == 0
+                || frame.rect.height == 0
+                || source_width == 0
+                || source_height == 0
+            {
+                return Err(TangerineError::MalformedSheet(format!(
+                    "frame {:?} is empty",
+                    frame.name
+                )));
+            }
+
+            let (width, height) = stored_size(frame);
+            let outside = |position: u32, size: u32, image_size: u32| {
+                position
+                    .checked_add(size)
+                    .is_none_or(|end| end > image_size)
+            };
+            if outside(frame.rect.x, width, image.width())
+                || outside(frame.rect.y, height, image.height())
+            {
+                return Err(TangerineError::MalformedSheet(format!(
+                    "frame {:?} is outside of the {}x{} image",
+                    frame.name,
+                    image.width(),
+                    image.height()
+                )));
+            }
+        }
+
+        let mut sprites = vec![];
+        for frame in &sheet.frames {
+            let SpriteRect { x, y, .. } = frame.rect;
+            let (width, height) = stored_size(frame);
+
+            let mut pixels = imageops::crop_imm(&image, x, y, width, height).to_image();
+            if frame.rotated {
+                pixels = imageops::rotate270(&pixels);
+            }
+
+            let [source_width, source_height] = frame.source_size;
+            let mut untrimmed = RgbaImage::new(source_width, source_height);
+            let [offset_x, offset_y] = frame.trimmed_offset;
+            imageops::replace(&mut untrimmed, &pixels, offset_x as _, offset_y as _);
+
+            let options = SpriteLoadOptions {
+                pivot: frame.pivot.unwrap_or(options.pivot),
+                ..options
+            };
+            let sprite = self.push_sprite(untrimmed, options)?;
+            self.names.push((frame.name.clone(), sprite));
+            sprites.push(sprite);
+        }
+
+        let animations = sheet
+            .tags
+            .iter()
+            .map(|tag| {
+                let mut frames = (tag.from..=tag.to)
+                    .map(|idx| AnimationFrame {
+                        sprite: sprites[idx],
+                        duration: sheet.frames[idx]
+                            .duration
+                            .unwrap_or_else(|| Duration::milliseconds(DEFAULT_FRAME_DURATION_MS)),
+                    })
+                    .collect::<Vec<_>>();
+                if tag.reverse {
+                    frames.reverse();
+                }
+
+                (
+                    tag.name.clone(),
+                    Animation {
+                        frames,
+                        mode: tag.mode,
+                    },
+                )
+            })
+            .collect();
+
+        Ok((
+            self,
+            ImportedSprites {
+                sprites,
+                animations,
+            },
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonSheet {
+    frames: JsonFrames,
+    #[serde(default)]
+    meta: JsonMeta,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonFrames {
+    Array(Vec<JsonFrame>),
+    Hash(OrderedFrames),
+}
+
+/// The frames of the hash format in the order they are written in, the
+/// indices of the frame tags refer to it.
+struct OrderedFrames(Vec<(String, JsonFrame)>);
+
+impl<'de> Deserialize<'de> for OrderedFrames {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = OrderedFrames;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map of frames")
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut frames = vec![];
+                while let Some(entry) = map.next_entry()? {
+                    frames.push(entry);
+                }
+                Ok(OrderedFrames(frames))
+            }
+        }
+
+        deserializer.deserialize_map(Visitor)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonFrame {
+    filename: Option<String>,
+    frame: JsonRect,
+    #[serde(default)]
+    rotated: bool,
+    sprite_source_size: Option<JsonRect>,
+    source_size: Option<JsonSize>,
+    pivot: Option<JsonPoint>,
+    duration: Option<i64>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+struct JsonRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+impl From<JsonRect> for SpriteRect {
+    fn from(rect: JsonRect) -> Self {
+        SpriteRect::new(rect.x, rect.y, rect.w, rect.h)
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonSize {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct JsonPoint {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct JsonMeta {
+    image: Option<String>,
+    #[serde(default)]
+    frame_tags: Vec<JsonTag>,
+}
+
+#[derive(Deserialize)]
+struct JsonTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default = "forward")]
+    direction: String,
+}
+
+fn forward() -> String {
+    "forward".to_string()
+}
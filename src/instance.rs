@@ -2,6 +2,8 @@ use bytemuck::{Pod, Zeroable};
 use cint::EncodedSrgb;
 use mint::{Vector2, Vector3};
 
+use crate::NineSlice;
+
 /// Defined the rotation and the scale of the sprite.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct SpriteTransform {
@@ -50,7 +52,8 @@ impl Default for SpriteTransform {
 /// Specification of how the sprite should appear on the screen.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct SpriteInstance {
-    /// The world position of the sprite's center.
+    /// The world position of the sprite's pivot, its center unless the
+    /// sprite was loaded with another pivot or `pivot` overrides it.
     pub position: Vector3<f32>,
     pub transform: SpriteTransform,
     /// RGB components of the sprite's colour.
@@ -59,6 +62,20 @@ pub struct SpriteInstance {
     /// Any values oustide of the `0..1` range will be clamped.
     /// All the sprites are considered non-premultiplied.
     pub opacity: f32,
+    /// Mirror the sprite along its vertical axis.
+    pub flip_x: bool,
+    /// Mirror the sprite along its horizontal axis.
+    pub flip_y: bool,
+    /// The part of the sprite to draw as normalized `[x, y, width, height]`
+    /// with the origin at the top left, applied after flipping. The rest of
+    /// the quad is not drawn, instead of the part being stretched over it.
+    pub sub_rect: Option<[f32; 4]>,
+    /// Replaces the pivot the sprite was loaded with, as normalized
+    /// coordinates with the origin at the top left.
+    pub pivot: Option<[f32; 2]>,
+    /// Draw the sprite's nine-slice patches at the given size instead of the
+    /// sprite itself. The sub-rect is ignored, the scale applies on top.
+    pub nine_slice: Option<NineSlice>,
 }
 
 impl Default for SpriteInstance {
@@ -68,6 +85,11 @@ impl Default for SpriteInstance {
             transform: Default::default(),
             color: [0xFFu8; 3].into(),
             opacity: 1.,
+            flip_x: false,
+            flip_y: false,
+            sub_rect: None,
+            pivot: None,
+            nine_slice: None,
         }
     }
 }
@@ -79,6 +101,10 @@ pub(crate) struct RawSpriteInstance {
     pub(crate) scale: [f32; 2],
     pub(crate) rotation_rad: f32,
     pub(crate) color: [f32; 4],
+    pub(crate) sub_rect: [f32; 4],
+    pub(crate) pivot: [f32; 2],
+    /// Bit 0 flips along X, bit 1 along Y, bit 2 replaces the sprite's pivot
+    pub(crate) flags: u32,
 }
 
 impl From<SpriteInstance> for RawSpriteInstance {
@@ -100,6 +126,11 @@ impl SpriteInstance {
             rotation_rad: self.transform.rotation_rad,
             scale: self.transform.scale.into(),
             color: [r, g, b, self.opacity].map(|component| component.clamp(0., 1.)),
+            sub_rect: self.sub_rect.unwrap_or([0., 0., 1., 1.]),
+            pivot: self.pivot.unwrap_or_default(),
+            flags: self.flip_x as u32
+                | ((self.flip_y as u32) << 1)
+                | ((self.pivot.is_some() as u32) << 2),
         }
     }
 
@@ -111,24 +142,39 @@ impl SpriteInstance {
             attributes: &[
                 wgpu::VertexAttribute {
                     offset: 0,
-                    shader_location: 2,
+                    shader_location: 5,
                     format: wgpu::VertexFormat::Float32x3,
                 },
                 wgpu::VertexAttribute {
                     offset: std::mem::size_of::<[f32; 3]>() as _,
-                    shader_location: 3,
+                    shader_location: 6,
                     format: wgpu::VertexFormat::Float32x2,
                 },
                 wgpu::VertexAttribute {
                     offset: std::mem::size_of::<[f32; 5]>() as _,
-                    shader_location: 4,
+                    shader_location: 7,
                     format: wgpu::VertexFormat::Float32,
                 },
                 wgpu::VertexAttribute {
                     offset: std::mem::size_of::<[f32; 6]>() as _,
-                    shader_location: 5,
+                    shader_location: 8,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 10]>() as _,
+                    shader_location: 9,
                     format: wgpu::VertexFormat::Float32x4,
                 },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 14]>() as _,
+                    shader_location: 10,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 16]>() as _,
+                    shader_location: 11,
+                    format: wgpu::VertexFormat::Uint32,
+                },
             ],
         }
     }
@@ -27,6 +27,27 @@ impl From<i32> for LayerIdentifier {
     }
 }
 
+/// The layers a camera draws.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LayerMask {
+    #[default]
+    All,
+    Only(Vec<LayerIdentifier>),
+    Except(Vec<LayerIdentifier>),
+}
+
+impl LayerMask {
+    /// Draw only the layers.
+    pub fn only<L: Into<LayerIdentifier>>(layers: impl IntoIterator<Item = L>) -> Self {
+        Self::Only(layers.into_iter().map(Into::into).collect())
+    }
+
+    /// Draw every layer but these.
+    pub fn except<L: Into<LayerIdentifier>>(layers: impl IntoIterator<Item = L>) -> Self {
+        Self::Except(layers.into_iter().map(Into::into).collect())
+    }
+}
+
 #[cfg(feature = "py")]
 impl FromPyObject<'_> for LayerIdentifier {
     fn extract(ob: &'_ pyo3::PyAny) -> pyo3::PyResult<Self> {
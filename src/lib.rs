@@ -11,30 +11,61 @@
 #![feature(c_size_t)]
 #![feature(ptr_metadata)]
 
+mod animation;
 mod atlas;
+mod builder;
 mod camera;
+mod camera_controller;
+mod error;
 mod frame;
+mod import;
 #[cfg(feature = "egui")]
 mod egui;
 mod ffi;
 mod instance;
 mod layer;
+mod manifest;
+mod nine_slice;
 mod packing;
 mod renderer;
+mod screenshot;
+mod shape;
+mod sheet;
 mod sprite;
 #[cfg(feature = "standalone")]
 mod standalone;
+mod text;
+#[cfg(feature = "tiled")]
+mod tiled;
+mod tilemap;
+#[cfg(feature = "ttf")]
+mod ttf;
 mod vertex;
 
+pub use animation::*;
 pub use atlas::*;
+pub use builder::*;
 pub use camera::*;
+pub use camera_controller::*;
+pub use error::*;
 #[allow(unused_imports)]
 pub use ffi::*;
+pub use import::*;
 pub use instance::*;
 pub use layer::*;
+pub use manifest::*;
+pub use nine_slice::*;
 pub use renderer::*;
+pub use shape::*;
+pub use sheet::*;
 pub use sprite::*;
 pub use frame::*;
+pub use text::*;
+#[cfg(feature = "tiled")]
+pub use tiled::*;
+pub use tilemap::*;
+#[cfg(feature = "ttf")]
+pub use ttf::*;
 
 #[cfg(feature = "standalone")]
 pub use standalone::*;
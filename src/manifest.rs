@@ -0,0 +1,245 @@
+use std::path::{Path, PathBuf};
+
+use image::{imageops, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::{packing::Node, AtlasPage, Renderer, SpriteIndex, SpriteLoadOptions, TangerineError};
+
+/// Description of a packed atlas saved next to its page images.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AtlasManifest {
+    pub pages: Vec<AtlasManifestPage>,
+    /// Every sprite of the atlas, ordered by its index.
+    pub sprites: Vec<AtlasManifestSprite>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AtlasManifestPage {
+    /// Path to the page's image relative to the manifest.
+    pub image: PathBuf,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AtlasManifestSprite {
+    pub index: SpriteIndex,
+    pub page: usize,
+    /// Top left corner of the sprite's own pixels in the page.
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub options: SpriteLoadOptions,
+    /// Names the sprite is registered under.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub names: Vec<String>,
+}
+
+impl Renderer {
+    /// Describe the current contents of the atlas.
+    pub fn atlas_manifest(&self, page_file_stem: &str) -> AtlasManifest {
+        let contents = &self.atlas_contents;
+
+        AtlasManifest {
+            pages: contents
+                .pages
+                .iter()
+                .enumerate()
+                .map(|(idx, page)| AtlasManifestPage {
+                    image: format!("{page_file_stem}.{idx}.png").into(),
+                    width: page.texture_size.x,
+                    height: page.texture_size.y,
+                })
+                .collect(),
+            sprites: contents
+                .images
+                .iter()
+                .enumerate()
+                .map(|(idx, image)| {
+                    let position = contents.content_position(idx);
+                    AtlasManifestSprite {
+                        index: idx,
+                        page: contents.sprite_pages[idx],
+                        x: position.x,
+                        y: position.y,
+                        width: image.width(),
+                        height: image.height(),
+                        options: contents.options[idx],
+                        names: {
+                            let mut names = contents
+                                .names
+                                .iter()
+                                .filter(|(_, sprite)| **sprite == idx)
+                                .map(|(name, _)| name.clone())
+                                .collect::<Vec<_>>();
+                            names.sort();
+                            names
+                        },
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Save the packed atlas as a JSON manifest and a PNG per page next to it,
+    /// named after the manifest as `<name>.<page>.png`.
+    pub fn save_atlas(&self, manifest_path: impl AsRef<Path>) -> Result<(), TangerineError> {
+        let manifest_path = manifest_path.as_ref();
+        let stem = manifest_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("atlas");
+        let directory = manifest_path.parent().unwrap_or(Path::new(""));
+
+        let contents = &self.atlas_contents;
+        let manifest = self.atlas_manifest(stem);
+
+        for (page_idx, page) in manifest.pages.iter().enumerate() {
+            let mut buffer = RgbaImage::new(page.width, page.height);
+            for idx in (0..contents.images.len()).filter(|i| contents.sprite_pages[*i] == page_idx)
+            {
+                let position = contents.positions[idx];
+                imageops::replace(
+                    &mut buffer,
+                    &contents.extruded_image(idx),
+                    position.x as _,
+                    position.y as _,
+                );
+            }
+            buffer.save(directory.join(&page.image))?;
+        }
+
+        let json = serde_json::to_vec_pretty(&manifest)
+            .map_err(|err| TangerineError::AtlasManifest(err.to_string()))?;
+        std::fs::write(manifest_path, json)?;
+        Ok(())
+    }
+
+    /// Replace every sprite of the atlas with the ones saved by `.save_atlas(..)`,
+    /// without decoding the original images and packing them again.
+    ///
+    /// Sprites added afterwards with `.finalize()` go onto new pages, use
+    /// `.finalize_and_repack()` to pack them together with the loaded ones.
+    pub fn load_atlas(&mut self, manifest_path: impl AsRef<Path>) -> Result<(), TangerineError> {
+        let manifest_path = manifest_path.as_ref();
+        let directory = manifest_path.parent().unwrap_or(Path::new(""));
+        let json = std::fs::read(manifest_path)?;
+        let manifest: AtlasManifest = serde_json::from_slice(&json)
+            .map_err(|err| TangerineError::AtlasManifest(err.to_string()))?;
+
+        let page_images = manifest
+            .pages
+            .iter()
+            .map(|page| Ok(image::open(directory.join(&page.image))?.into_rgba8()))
+            .collect::<Result<Vec<_>, TangerineError>>()?;
+
+        // Pages larger than a texture can be couldn't be uploaded
+        let max_size = self.atlas_contents.max_page_size;
+        if let Some((page_idx, page)) = page_images
+            .iter()
+            .enumerate()
+            .find(|(_, page)| page.width() > max_size || page.height() > max_size)
+        {
+            return Err(TangerineError::AtlasManifest(format!(
+                "page {page_idx} is {}x{} pixels but the maximum texture size is {max_size}",
+                page.width(),
+                page.height()
+            )));
+        }
+
+        let mut sprites = manifest.sprites;
+        sprites.sort_by_key(|sprite| sprite.index);
+        validate_sprites(&sprites, &page_images)?;
+
+        let contents = &mut self.atlas_contents;
+        contents.images.clear();
+        contents.options.clear();
+        contents.positions.clear();
+        contents.sprite_pages.clear();
+        contents.names.clear();
+
+        for sprite in sprites {
+            let image = imageops::crop_imm(
+                &page_images[sprite.page],
+                sprite.x,
+                sprite.y,
+                sprite.width,
+                sprite.height,
+            )
+            .to_image();
+
+            let extrude = sprite.options.extrude;
+            contents.images.push(image);
+            contents.options.push(sprite.options);
+            contents
+                .positions
+                .push([sprite.x - extrude, sprite.y - extrude].into());
+            contents.sprite_pages.push(sprite.page);
+            contents
+                .names
+                .extend(sprite.names.into_iter().map(|name| (name, sprite.index)));
+        }
+
+        // The packing trees aren't saved, so the loaded pages are considered full
+        contents.pages = page_images
+            .iter()
+            .map(|image| AtlasPage {
+                packer: Node::full(contents.max_page_size),
+                texture_size: [image.width(), image.height()].into(),
+            })
+            .collect();
+
+        self.atlas_pages.clear();
+        for page_idx in 0..self.atlas_contents.pages.len() {
+            self.upload_atlas_page(page_idx);
+        }
+        self.rebuild_sprite_meshes();
+
+        Ok(())
+    }
+}
+
+/// Make sure the sprites, sorted by their index, can be cut out of the pages
+/// and that every index from zero on is used exactly once.
+fn validate_sprites(
+    sprites: &[AtlasManifestSprite],
+    page_images: &[RgbaImage],
+) -> Result<(), TangerineError> {
+    let invalid = |reason: String| Err(TangerineError::AtlasManifest(reason));
+
+    for (expected_idx, sprite) in sprites.iter().enumerate() {
+        let idx = sprite.index;
+        if idx != expected_idx {
+            return if idx > expected_idx {
+                invalid(format!("sprite {expected_idx} is missing"))
+            } else {
+                invalid(format!("sprite {idx} is listed more than once"))
+            };
+        }
+
+        let Some(page) = page_images.get(sprite.page) else {
+            return invalid(format!(
+                "sprite {idx} is on page {} but there are only {} pages",
+                sprite.page,
+                page_images.len()
+            ));
+        };
+
+        // Widened so that bogus sizes can't overflow
+        let extrude = sprite.options.extrude as u64;
+        let within = |position: u32, size: u32, page_size: u32| {
+            let position = position as u64;
+            position >= extrude && position + size as u64 + extrude <= page_size as u64
+        };
+        let fits = within(sprite.x, sprite.width, page.width())
+            && within(sprite.y, sprite.height, page.height());
+        if !fits {
+            return invalid(format!(
+                "sprite {idx} with its extruded edges is outside of page {}",
+                sprite.page
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use mint::Vector2;
+
+use crate::{SpriteDrawData, SpriteInstance, SpriteTransform};
+
+/// How the edges and the centre of a nine-slice sprite fill the space
+/// between its corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum NineSliceFill {
+    /// Stretch them over the whole space.
+    #[default]
+    Stretch,
+    /// Repeat them at their own size, cropping the last repetition.
+    Tile,
+}
+
+/// A sprite drawn as nine patches, see `SpriteLoadOptions::nine_slice`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NineSlice {
+    /// Size of the whole sprite in world units
+    pub size: Vector2<f32>,
+    pub fill: NineSliceFill,
+}
+
+/// A run of patches along one axis, normalized in the texture and in world
+/// units in the world.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    tex_start: f32,
+    tex_len: f32,
+    world_len: f32,
+}
+
+/// Split an axis into the start border, the middle and the end border.
+fn segments(
+    [start_border, end_border]: [f32; 2],
+    quad_len: f32,
+    size: f32,
+    fill: NineSliceFill,
+) -> Vec<Segment> {
+    let start_world = start_border * quad_len;
+    let end_world = end_border * quad_len;
+    // Borders which don't fit are shrunk instead of overlapping
+    let shrink = if start_world + end_world > size {
+        size / (start_world + end_world)
+    } else {
+        1.
+    };
+
+    let mut segments = vec![Segment {
+        tex_start: 0.,
+        tex_len: start_border,
+        world_len: start_world * shrink,
+    }];
+
+    let middle_tex = 1. - start_border - end_border;
+    let middle_world = size - (start_world + end_world) * shrink;
+    if middle_tex > 0. && middle_world > 0. {
+        match fill {
+            NineSliceFill::Stretch => segments.push(Segment {
+                tex_start: start_border,
+                tex_len: middle_tex,
+                world_len: middle_world,
+            }),
+            NineSliceFill::Tile => {
+                let tile = middle_tex * quad_len;
+                let count = (middle_world / tile).ceil() as usize;
+                segments.extend((0..count).map(|idx| {
+                    let world_len = (middle_world - idx as f32 * tile).min(tile);
+                    Segment {
+                        tex_start: start_border,
+                        tex_len: middle_tex * world_len / tile,
+                        world_len,
+                    }
+                }));
+            }
+        }
+    }
+
+    segments.push(Segment {
+        tex_start: 1. - end_border,
+        tex_len: end_border,
+        world_len: end_world * shrink,
+    });
+    segments.retain(|segment| segment.tex_len > 0. && segment.world_len > 0.);
+    segments
+}
+
+/// Split the instance into the patches of the sprite. Every patch is drawn
+/// as the part of the sprite it covers, placed by its top left corner.
+pub(crate) fn nine_slice_instances(
+    sprite: &SpriteDrawData,
+    instance: &SpriteInstance,
+    nine_slice: NineSlice,
+) -> Vec<SpriteInstance> {
+    let [mut left, mut top, mut right, mut bottom] = sprite.borders;
+    // Sub-rects are applied after flipping, so the borders switch sides
+    if instance.flip_x {
+        std::mem::swap(&mut left, &mut right);
+    }
+    if instance.flip_y {
+        std::mem::swap(&mut top, &mut bottom);
+    }
+
+    let [quad_width, quad_height] = sprite.quad_size;
+    let Vector2 {
+        x: width,
+        y: height,
+    } = nine_slice.size;
+    let columns = segments([left, right], quad_width, width, nine_slice.fill);
+    let rows = segments([top, bottom], quad_height, height, nine_slice.fill);
+
+    let [pivot_x, pivot_y] = instance.pivot.unwrap_or(sprite.pivot);
+    let Vector2 {
+        x: scale_x,
+        y: scale_y,
+    } = instance.transform.scale;
+    let (sin, cos) = instance.transform.rotation_rad.sin_cos();
+
+    let mut patches = Vec::with_capacity(columns.len() * rows.len());
+    let mut y = pivot_y * height;
+    for row in rows.iter() {
+        let mut x = -pivot_x * width;
+        for column in columns.iter() {
+            // Rotated around the instance's position the same way as sprites
+            let [offset_x, offset_y] = [x * scale_x, y * scale_y];
+            patches.push(SpriteInstance {
+                position: [
+                    instance.position.x + offset_x * cos - offset_y * sin,
+                    instance.position.y + offset_x * sin + offset_y * cos,
+                    instance.position.z,
+                ]
+                .into(),
+                transform: SpriteTransform {
+                    scale: [
+                        scale_x * column.world_len / (column.tex_len * quad_width),
+                        scale_y * row.world_len / (row.tex_len * quad_height),
+                    ]
+                    .into(),
+                    rotation_rad: instance.transform.rotation_rad,
+                },
+                sub_rect: Some([column.tex_start, row.tex_start, column.tex_len, row.tex_len]),
+                pivot: Some([column.tex_start, row.tex_start]),
+                nine_slice: None,
+                ..*instance
+            });
+            x += column.world_len;
+        }
+        y -= row.world_len;
+    }
+
+    patches
+}
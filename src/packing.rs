@@ -1,8 +1,9 @@
 use std::cell::RefCell;
 
-use image::RgbaImage;
 use mint::Vector2;
 
+/// A node of the binary tree packer covering the `topleft..botright` area,
+/// the bottom right corner is exclusive.
 #[derive(Debug)]
 pub(crate) struct Node {
     pub(crate) children: Option<[Box<RefCell<Node>>; 2]>,
@@ -12,42 +13,65 @@ pub(crate) struct Node {
 }
 
 impl Node {
-    pub fn insert(&mut self, image: &RgbaImage, index: usize) -> bool {
+    /// An empty node spanning a `size` by `size` square.
+    pub fn root(size: u32) -> Self {
+        Node {
+            children: None,
+            topleft: [0; 2].into(),
+            botright: [size; 2].into(),
+            image_index: None,
+        }
+    }
+
+    /// A node spanning a `size` by `size` square with no free space left.
+    pub fn full(size: u32) -> Self {
+        Node {
+            image_index: Some(usize::MAX),
+            ..Node::root(size)
+        }
+    }
+
+    /// Insert an image of the given size into the tree, returns the top left
+    /// corner where it was placed or `None` if there is no place left for it.
+    pub fn insert(&mut self, img_size: Vector2<u32>, index: usize) -> Option<Vector2<u32>> {
         match &self.children {
-            Some([left, right]) => {
-                if left.borrow_mut().insert(image, index) {
-                    true
-                } else {
-                    right.borrow_mut().insert(image, index)
-                }
-            }
+            Some([left, right]) => left
+                .borrow_mut()
+                .insert(img_size, index)
+                .or_else(|| right.borrow_mut().insert(img_size, index)),
 
             None => {
                 if self.image_index.is_some() {
-                    return false;
+                    return None;
                 }
 
-                let img_size: Vector2<u32> = [image.width(), image.height()].into();
+                let node_size: Vector2<u32> = [
+                    self.botright.x - self.topleft.x,
+                    self.botright.y - self.topleft.y,
+                ]
+                .into();
 
-                if self.botright.x < img_size.x || self.botright.y < img_size.y {
-                    return false;
+                if node_size.x < img_size.x || node_size.y < img_size.y {
+                    return None;
                 }
 
-                if img_size == self.botright {
+                if img_size == node_size {
                     self.image_index = Some(index);
-                    return true;
+                    return Some(self.topleft);
                 }
 
-                let dx = (self.botright.x - self.topleft.x) - img_size.x;
-                let dy = (self.botright.y - self.topleft.y) - img_size.y;
+                let dx = node_size.x - img_size.x;
+                let dy = node_size.y - img_size.y;
 
+                // Cut off a strip fitting the image along the axis with the
+                // most space left, the image is then fit into the strip
                 let children = if dx > dy {
                     [
                         Node {
                             children: None,
                             topleft: self.topleft,
-                            botright: [self.topleft.x + img_size.x - 1, self.botright.y].into(),
-                            image_index: Some(index),
+                            botright: [self.topleft.x + img_size.x, self.botright.y].into(),
+                            image_index: None,
                         },
                         Node {
                             children: None,
@@ -61,8 +85,8 @@ impl Node {
                         Node {
                             children: None,
                             topleft: self.topleft,
-                            botright: [self.botright.x, self.topleft.y + img_size.y - 1].into(),
-                            image_index: Some(index),
+                            botright: [self.botright.x, self.topleft.y + img_size.y].into(),
+                            image_index: None,
                         },
                         Node {
                             children: None,
@@ -73,8 +97,10 @@ impl Node {
                     ]
                 };
 
-                self.children = Some(children.map(|a| Box::new(RefCell::new(a))));
-                true
+                let [strip, rest] = children.map(|a| Box::new(RefCell::new(a)));
+                let position = strip.borrow_mut().insert(img_size, index);
+                self.children = Some([strip, rest]);
+                position
             }
         }
     }
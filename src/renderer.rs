@@ -1,4 +1,4 @@
-use std::{alloc::Allocator, cell::OnceCell};
+use std::{alloc::Allocator, cell::OnceCell, ops::Range, sync::Arc};
 
 use chrono::{Duration, NaiveDateTime, Utc};
 use cint::EncodedSrgb;
@@ -8,60 +8,132 @@ use crate::egui::EguiIntegration;
 #[cfg(feature = "egui")]
 use egui::Context;
 
+use image::RgbaImage;
 use mint::Vector2;
 use wgpu::{util::DeviceExt, Buffer, BufferDescriptor, BufferUsages};
 use winit::{dpi::PhysicalSize, event::WindowEvent, window::Window};
 
 use crate::{
-    frame::{FrameData, SpriteRenderData},
-    vertex::Vertex,
-    AtlasBuilder, Camera, CameraRaw, LayerIdentifier, RawSpriteInstance, SpriteDrawData,
-    SpriteInstance,
+    frame::FrameData,
+    nine_slice::nine_slice_instances,
+    tilemap::ChunkMesh,
+    vertex::{ShapeVertex, Vertex},
+    AtlasBuilder, AtlasContents, AtlasPageTexture, Camera, LayerIdentifier, LayerMask,
+    RawSpriteInstance, RendererBuilder, SpriteDrawData, SpriteIndex, SpriteInstance,
+    TangerineError, ViewportRect, MAIN_CAMERA,
 };
 
+/// A single draw call of a frame, recorded in the order of the layers.
+enum DrawCommand {
+    Sprites {
+        sprite_idx: SpriteIndex,
+        instances: Range<u32>,
+        /// The tilemap chunk the instances are in, instead of the frame's
+        chunk: Option<Arc<ChunkMesh>>,
+    },
+    Shapes {
+        vertices: Range<u32>,
+    },
+}
+
+/// The pipeline the render pass currently draws with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BoundPipeline {
+    Sprites,
+    Shapes,
+}
+
+/// A camera together with the part of the target it draws into.
+pub(crate) struct RenderCamera {
+    pub name: String,
+    pub camera: Camera,
+    pub viewport: ViewportRect,
+    pub layers: LayerMask,
+    /// Camera's reflection on the GPU
+    pub buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+}
+
+/// The layer mask of a camera with the named layers resolved.
+enum ResolvedLayers {
+    All,
+    Only(Vec<i32>),
+    Except(Vec<i32>),
+}
+
+impl ResolvedLayers {
+    fn contains(&self, layer: i32) -> bool {
+        match self {
+            ResolvedLayers::All => true,
+            ResolvedLayers::Only(layers) => layers.contains(&layer),
+            ResolvedLayers::Except(layers) => !layers.contains(&layer),
+        }
+    }
+}
+
+/// Format of the texture headless renderers draw into.
+pub const OFFSCREEN_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Where the frames drawn by the [`Renderer`] end up.
+pub(crate) enum RenderTarget {
+    /// Frames are presented onto the surface of a window.
+    Window {
+        surface: wgpu::Surface,
+        config: wgpu::SurfaceConfiguration,
+        /// Window everything is presented onto
+        window: Window,
+    },
+    /// Frames are drawn into a texture that is never presented.
+    Offscreen { texture: wgpu::Texture },
+}
+
 /// The main object used for drawing. Use `.atlas()` to load new sprites and
 /// `.begin_frame()` to draw them.
 pub struct Renderer {
     /*** WGPU Data ***/
-    pub(crate) surface: wgpu::Surface,
     pub(crate) device: wgpu::Device,
     pub(crate) queue: wgpu::Queue,
-    pub(crate) config: wgpu::SurfaceConfiguration,
     pub(crate) render_pipeline: wgpu::RenderPipeline,
 
-    /*** Window Data ***/
-    /// Actual size of the window in pixels (?)
+    /*** Target Data ***/
+    /// Actual size of the target in pixels
     pub(crate) size: PhysicalSize<u32>,
-    /// Window everything is presented onto
-    pub(crate) window: Window,
+    /// The window or the texture everything is drawn onto
+    pub(crate) target: RenderTarget,
 
     /*** Atlas ***/
-    /// A stitched texture generate by the AtlasBuilder
-    pub(crate) atlas_texture: (wgpu::Texture, wgpu::TextureView),
+    /// Stitched textures generated by the AtlasBuilder, one per page
+    pub(crate) atlas_pages: Vec<AtlasPageTexture>,
     /// The layout of the atlas bind group
     pub(crate) atlas_bind_group_layout: wgpu::BindGroupLayout,
     /// Sampler used by the atlas
     pub(crate) atlas_sampler: wgpu::Sampler,
-
-    /*** Camera ***/
-    /// The camera from the perspective of which everything is rendered
-    pub(crate) camera: Camera,
-    /// Camera's reflection on the GPU
-    pub(crate) camera_buffer: wgpu::Buffer,
+    /// Every sprite in the atlas and its placement
+    pub(crate) atlas_contents: AtlasContents,
+    /// How many pixels of a sprite span a world unit, when `None` every
+    /// sprite is one unit tall
+    pub(crate) pixels_per_unit: Option<f32>,
+
+    /*** Cameras ***/
+    /// The cameras everything is rendered from, in the order they are drawn.
+    /// The first one is the main camera
+    pub(crate) cameras: Vec<RenderCamera>,
+    /// The layout of the bind group of every camera
+    pub(crate) camera_bind_group_layout: wgpu::BindGroupLayout,
     /// The background color to clear the buffer with
     pub(crate) clear_color: Option<EncodedSrgb<u8>>,
 
+    /*** Multisampling ***/
+    /// The amount of samples per pixel, `1` when multisampling is disabled
+    pub(crate) sample_count: u32,
+    /// The texture drawn into and then resolved onto the target when multisampling
+    pub(crate) multisampled_texture: Option<wgpu::Texture>,
+
     /*** Misc ***/
     /// To avoid using magic numbers you can name layers. The layer names are
     /// resolved from this table.
     pub(crate) named_layers: hashbrown::HashMap<String, i32>,
 
-    /*** Bind Groups ***/
-    /// Bind Group for data that rarely changes
-    pub(crate) cold_bind_group: wgpu::BindGroup,
-    /// Bind Group for data that changes often
-    pub(crate) hot_bind_group: wgpu::BindGroup,
-
     /*** Instances ***/
     /// The amount of instances the `instance_buffer` can take
     pub(crate) instance_count: u64,
@@ -76,75 +148,172 @@ pub struct Renderer {
     /// Indices into the vertex buffer
     pub(crate) index_buffer: Buffer,
 
+    /*** Shapes ***/
+    /// Pipeline of the untextured shapes
+    pub(crate) shape_pipeline: wgpu::RenderPipeline,
+    /// The amount of vertices the `shape_buffer` can take
+    pub(crate) shape_vertex_count: u64,
+    /// Vertices of the shapes of the current frame
+    pub(crate) shape_buffer: Buffer,
+
     /*** Timers ***/
     /// The timestamp of the first rendered frame
     pub(crate) first_render_ts: OnceCell<NaiveDateTime>,
     pub(crate) last_render_ts: Option<NaiveDateTime>,
     pub(crate) delta_time: Duration,
 
+    /*** Screenshots ***/
+    /// Whether the next drawn frame should be read back
+    pub(crate) screenshot_requested: bool,
+    /// The last frame read back after being requested
+    pub(crate) screenshot: Option<RgbaImage>,
+
     /*** EGUI Integration ***/
+    /// Only present when rendering into a window.
     #[cfg(feature = "egui")]
-    pub egui_integration: EguiIntegration,
+    pub egui_integration: Option<EguiIntegration>,
 }
 
 #[cfg(feature = "sync-new")]
 impl From<Window> for Renderer {
     fn from(window: Window) -> Self {
-        pollster::block_on(Renderer::new(window))
+        Renderer::try_from_window(window).unwrap()
+    }
+}
+
+#[cfg(feature = "sync-new")]
+impl Renderer {
+    /// Creates a renderer presenting onto the window, failing instead of
+    /// panicking if the GPU objects can't be created.
+    pub fn try_from_window(window: Window) -> Result<Self, TangerineError> {
+        RendererBuilder::default().build(window)
+    }
+
+    /// Creates a renderer that draws into an offscreen texture of the given size.
+    pub fn headless(size: impl Into<PhysicalSize<u32>>) -> Self {
+        Renderer::try_headless(size).unwrap()
+    }
+
+    /// Same as `Renderer::headless` but fails instead of panicking.
+    pub fn try_headless(size: impl Into<PhysicalSize<u32>>) -> Result<Self, TangerineError> {
+        RendererBuilder::default().build_headless(size)
     }
 }
 
 impl Renderer {
+    /// Creates a builder to configure the adapter, the presentation and the
+    /// pipeline before creating a renderer.
+    pub fn builder() -> RendererBuilder {
+        RendererBuilder::default()
+    }
+
     /// Creates all the required objects on the GPU to draw onto the Window
-    async fn new(window: Window) -> Self {
-        let size = window.inner_size();
+    pub async fn try_new(window: Window) -> Result<Self, TangerineError> {
+        RendererBuilder::default().build_async(window).await
+    }
+
+    /// Creates all the required objects on the GPU to draw into an offscreen
+    /// texture, falling back onto a software adapter if there is no GPU.
+    pub async fn try_new_headless(
+        size: impl Into<PhysicalSize<u32>>,
+    ) -> Result<Self, TangerineError> {
+        RendererBuilder::default().build_headless_async(size).await
+    }
 
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            dx12_shader_compiler: Default::default(),
+    /// Creates a texture that can be drawn into and then read back.
+    pub(crate) fn create_target_texture(
+        device: &wgpu::Device,
+        size: PhysicalSize<u32>,
+        format: wgpu::TextureFormat,
+    ) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            label: None,
+            view_formats: &[],
+        })
+    }
+
+    /// Creates the texture multisampled frames are drawn into before being resolved.
+    pub(crate) fn create_multisampled_texture(
+        device: &wgpu::Device,
+        size: PhysicalSize<u32>,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            label: None,
+            view_formats: &[],
+        })
+    }
+
+    /// Creates the uniform buffer and the bind group of a camera.
+    pub(crate) fn create_render_camera(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        name: String,
+        camera: Camera,
+        viewport: ViewportRect,
+    ) -> RenderCamera {
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::bytes_of(&camera.raw()),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
         });
 
-        let surface = unsafe { instance.create_surface(&window) }.unwrap();
-
-        let adapter = instance
-            .enumerate_adapters(wgpu::Backends::all())
-            .find(|adapter| adapter.is_surface_supported(&surface))
-            .unwrap();
-
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    features: wgpu::Features::empty(),
-                    limits: if cfg!(target_arch = "wasm32") {
-                        wgpu::Limits::downlevel_webgl2_defaults()
-                    } else {
-                        wgpu::Limits::default()
-                    },
-                    label: None,
-                },
-                None,
-            )
-            .await
-            .unwrap();
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+            label: None,
+        });
 
-        let surface_caps = surface.get_capabilities(&adapter);
-        let surface_format = surface_caps
-            .formats
-            .iter()
-            .copied()
-            .find(|f| f.is_srgb())
-            .unwrap_or(surface_caps.formats[0]);
+        RenderCamera {
+            name,
+            camera,
+            viewport,
+            layers: LayerMask::All,
+            buffer,
+            bind_group,
+        }
+    }
 
-        let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: surface_format,
-            width: size.width,
-            height: size.height,
-            present_mode: surface_caps.present_modes[0],
-            alpha_mode: surface_caps.alpha_modes[0],
-            view_formats: vec![],
+    /// Creates the pipeline, the atlas and the camera shared by all the targets.
+    pub(crate) fn from_target(
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        size: PhysicalSize<u32>,
+        target: RenderTarget,
+        builder: &RendererBuilder,
+    ) -> Self {
+        let target_format = match &target {
+            RenderTarget::Window { config, .. } => config.format,
+            RenderTarget::Offscreen { .. } => OFFSCREEN_TEXTURE_FORMAT,
         };
-        surface.configure(&device, &config);
+
+        let sample_count = builder.sample_count;
+        let multisampled_texture = (sample_count > 1)
+            .then(|| Self::create_multisampled_texture(&device, size, target_format, sample_count));
 
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: None,
@@ -229,7 +398,7 @@ impl Renderer {
                 module: &shader,
                 entry_point: "fs_main",
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
+                    format: target_format,
                     blend: Some(wgpu::BlendState::ALPHA_BLENDING),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
@@ -245,77 +414,101 @@ impl Renderer {
             },
             depth_stencil: None,
             multisample: wgpu::MultisampleState {
-                count: 1,
+                count: sample_count,
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
             multiview: None,
         });
 
-        let atlas_texture = device.create_texture(&wgpu::TextureDescriptor {
-            size: wgpu::Extent3d {
-                width: 1,
-                height: 1,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        let shape_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: None,
-            view_formats: &[],
+            source: wgpu::ShaderSource::Wgsl(include_str!("./shaders/shapes.wgsl").into()),
         });
 
-        let atlas_texture_view = atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let shape_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &[&hot_bind_group_layout],
+                push_constant_ranges: &[],
+            });
 
-        let cold_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &cold_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&atlas_texture_view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&sampler),
-                },
-            ],
+        let shape_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: None,
+            layout: Some(&shape_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shape_shader,
+                entry_point: "vs_main",
+                buffers: &[ShapeVertex::layout()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shape_shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                // Polygons may be wound either way
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
         });
 
-        let camera = Camera::default();
-
-        let camera_buffer = device.create_buffer(&BufferDescriptor {
+        let shape_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: None,
-            size: std::mem::size_of::<CameraRaw>() as u64,
-            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            size: std::mem::size_of::<ShapeVertex>() as _,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
-        queue.write_buffer(&camera_buffer, 0, bytemuck::bytes_of(&camera.raw()));
 
-        let hot_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &hot_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: camera_buffer.as_entire_binding(),
-            }],
-            label: None,
-        });
+        let camera = Camera {
+            aspect_ratio: size.width as f32 / size.height as f32,
+            ..Default::default()
+        };
+
+        let main_camera = Self::create_render_camera(
+            &device,
+            &hot_bind_group_layout,
+            MAIN_CAMERA.to_string(),
+            camera,
+            ViewportRect::FULL,
+        );
 
         Self {
-            clear_color: None,
-            cold_bind_group,
-            hot_bind_group,
+            clear_color: builder.clear_color,
+            sample_count,
+            multisampled_texture,
             atlas_bind_group_layout: cold_bind_group_layout,
 
             #[cfg(feature = "egui")]
-            egui_integration: { EguiIntegration::new(&window, &device, surface_format) },
+            egui_integration: match &target {
+                RenderTarget::Window { window, .. } => {
+                    Some(EguiIntegration::new(window, &device, target_format))
+                }
+                RenderTarget::Offscreen { .. } => None,
+            },
 
-            camera: Default::default(),
-            camera_buffer,
+            cameras: vec![main_camera],
+            camera_bind_group_layout: hot_bind_group_layout,
 
-            atlas_texture: (atlas_texture, atlas_texture_view),
+            atlas_pages: vec![],
+            atlas_contents: AtlasContents::new(device.limits().max_texture_dimension_2d),
+            pixels_per_unit: builder.pixels_per_unit,
             instance_count: 1,
             instance_buffer,
             size,
@@ -323,17 +516,22 @@ impl Renderer {
             atlas_sampler: sampler,
             vertex_buffer,
             index_buffer,
-            window,
-            surface,
+            target,
             device,
             queue,
-            config,
             render_pipeline,
             named_layers: [].into(),
 
+            shape_pipeline,
+            shape_vertex_count: 1,
+            shape_buffer,
+
             first_render_ts: OnceCell::new(),
             last_render_ts: None,
             delta_time: Duration::zero(),
+
+            screenshot_requested: false,
+            screenshot: None,
         }
     }
 }
@@ -343,8 +541,10 @@ impl Renderer {
     /// the input was consumed and `false` otherwise.
     pub fn input(&mut self, event: &WindowEvent) -> bool {
         #[cfg(feature = "egui")]
-        if self.egui_integration.input(event) {
-            return true;
+        if let Some(egui_integration) = &mut self.egui_integration {
+            if egui_integration.input(event) {
+                return true;
+            }
         }
 
         match event {
@@ -365,11 +565,31 @@ impl Renderer {
         AtlasBuilder {
             renderer: self,
             rgba: vec![],
+            options: vec![],
+            default_options: Default::default(),
             statically_dispatched_sprites: [],
             dynamically_dispatched_sprites: vec![],
+            names: vec![],
         }
     }
 
+    /// Change how many pixels of a sprite span a world unit for the sprites
+    /// that don't specify it themselves, `None` makes every sprite one unit tall.
+    pub fn set_pixels_per_unit(&mut self, pixels_per_unit: Option<f32>) {
+        self.pixels_per_unit = pixels_per_unit;
+        self.rebuild_sprite_meshes();
+    }
+
+    /// The time between the last two drawn frames.
+    pub fn delta_time(&self) -> Duration {
+        self.delta_time
+    }
+
+    /// The sprite registered under the name, see `AtlasBuilder::add_named_sprite(..)`.
+    pub fn sprite(&self, name: &str) -> Option<SpriteIndex> {
+        self.atlas_contents.names.get(name).copied()
+    }
+
     fn sampling_options() -> wgpu::SamplerDescriptor<'static> {
         wgpu::SamplerDescriptor {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
@@ -382,26 +602,133 @@ impl Renderer {
         }
     }
 
-    /// Changes the size of the output surface and refreshes the camera.
+    /// Changes the size of the output surface or texture and refreshes the camera.
     pub fn resize(&mut self, new_size: PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 {
             self.size = new_size;
-            self.config.width = new_size.width;
-            self.config.height = new_size.height;
-            self.surface.configure(&self.device, &self.config);
-            self.mutate_camera(|camera| {
-                camera.aspect_ratio = new_size.width as f32 / new_size.height as f32
-            });
+            match &mut self.target {
+                RenderTarget::Window {
+                    surface, config, ..
+                } => {
+                    config.width = new_size.width;
+                    config.height = new_size.height;
+                    surface.configure(&self.device, config);
+                }
+                RenderTarget::Offscreen { texture } => {
+                    *texture = Self::create_target_texture(
+                        &self.device,
+                        new_size,
+                        OFFSCREEN_TEXTURE_FORMAT,
+                    );
+                }
+            }
+            if let Some(multisampled_texture) = &mut self.multisampled_texture {
+                *multisampled_texture = Self::create_multisampled_texture(
+                    &self.device,
+                    new_size,
+                    multisampled_texture.format(),
+                    self.sample_count,
+                );
+            }
+            for idx in 0..self.cameras.len() {
+                self.mutate_camera_at(idx, |camera, viewport| {
+                    camera.aspect_ratio = viewport.aspect_ratio(new_size)
+                });
+            }
         }
     }
 
-    /// Change the camera data and automatically refresh the buffer
+    /// Change the main camera data and automatically refresh the buffer
     pub fn mutate_camera(&mut self, camera: impl FnOnce(&mut Camera)) {
-        camera(&mut self.camera);
+        self.mutate_camera_at(0, |main_camera, _| camera(main_camera));
+    }
+
+    /// Change the data of the named camera and automatically refresh its buffer.
+    pub fn mutate_named_camera(
+        &mut self,
+        name: &str,
+        camera: impl FnOnce(&mut Camera),
+    ) -> Result<(), TangerineError> {
+        let idx = self.camera_index(name)?;
+        self.mutate_camera_at(idx, |named_camera, _| camera(named_camera));
+        Ok(())
+    }
+
+    /// Add a camera drawing into the part of the target after the cameras
+    /// added before, or replace the camera with the same name. Its aspect
+    /// ratio is kept in line with the viewport and it draws every layer.
+    pub fn add_camera(
+        &mut self,
+        name: impl ToString,
+        camera: Camera,
+        viewport: impl Into<ViewportRect>,
+    ) {
+        let viewport = viewport.into();
+        let render_camera = Self::create_render_camera(
+            &self.device,
+            &self.camera_bind_group_layout,
+            name.to_string(),
+            Camera {
+                aspect_ratio: viewport.aspect_ratio(self.size),
+                ..camera
+            },
+            viewport,
+        );
+
+        match self.camera_index(&render_camera.name) {
+            Ok(idx) => self.cameras[idx] = render_camera,
+            Err(_) => self.cameras.push(render_camera),
+        }
+    }
+
+    /// Remove the named camera, the main camera can't be removed.
+    pub fn remove_camera(&mut self, name: &str) -> Option<Camera> {
+        match self.camera_index(name) {
+            Ok(idx) if idx > 0 => Some(self.cameras.remove(idx).camera),
+            _ => None,
+        }
+    }
+
+    /// Move the named camera to another part of the target.
+    pub fn set_camera_viewport(
+        &mut self,
+        name: &str,
+        viewport: impl Into<ViewportRect>,
+    ) -> Result<(), TangerineError> {
+        let idx = self.camera_index(name)?;
+        let size = self.size;
+        self.cameras[idx].viewport = viewport.into();
+        self.mutate_camera_at(idx, |camera, viewport| {
+            camera.aspect_ratio = viewport.aspect_ratio(size)
+        });
+        Ok(())
+    }
+
+    /// Change the layers the named camera draws.
+    pub fn set_camera_layers(
+        &mut self,
+        name: &str,
+        layers: LayerMask,
+    ) -> Result<(), TangerineError> {
+        let idx = self.camera_index(name)?;
+        self.cameras[idx].layers = layers;
+        Ok(())
+    }
+
+    fn camera_index(&self, name: &str) -> Result<usize, TangerineError> {
+        self.cameras
+            .iter()
+            .position(|render_camera| render_camera.name == name)
+            .ok_or_else(|| TangerineError::UnknownCamera(name.to_string()))
+    }
+
+    fn mutate_camera_at(&mut self, idx: usize, camera: impl FnOnce(&mut Camera, ViewportRect)) {
+        let render_camera = &mut self.cameras[idx];
+        camera(&mut render_camera.camera, render_camera.viewport);
         self.queue.write_buffer(
-            &self.camera_buffer,
+            &render_camera.buffer,
             0,
-            bytemuck::bytes_of(&self.camera.raw()),
+            bytemuck::bytes_of(&render_camera.camera.raw()),
         );
     }
 
@@ -409,17 +736,61 @@ impl Renderer {
         self.named_layers.insert(name.to_string(), constant);
     }
 
-    pub fn draw_frame<A: Allocator>(
-        &mut self,
-        frame: FrameData<A>,
-    ) -> Result<(), wgpu::SurfaceError> {
+    pub fn draw_frame<A: Allocator>(&mut self, frame: FrameData<A>) -> Result<(), TangerineError> {
         let FrameData {
-            mut sprite_data, ..
+            sprite_data,
+            shape_data,
+            shape_vertices,
+            tilemap_data,
+            ..
         } = frame;
 
-        let output = self.surface.get_current_texture()?;
+        if let Some(unknown) = sprite_data
+            .iter()
+            .find(|sprite| sprite.index >= self.sprites.len())
+        {
+            return Err(TangerineError::UnknownSprite(unknown.index));
+        }
+
+        let (output, view) = match &self.target {
+            RenderTarget::Window { surface, .. } => {
+                let output = surface.get_current_texture()?;
+                let view = output
+                    .texture
+                    .create_view(&wgpu::TextureViewDescriptor::default());
+                (Some(output), view)
+            }
+            RenderTarget::Offscreen { texture } => (
+                None,
+                texture.create_view(&wgpu::TextureViewDescriptor::default()),
+            ),
+        };
+
+        // Nine-slice sprites are drawn as one instance per patch
+        let mut sprite_data = sprite_data
+            .into_iter()
+            .flat_map(|sprite| {
+                let layer = self.resolve_layer_ord(sprite.layer.as_ref());
+                let instances = match sprite.instance.nine_slice {
+                    Some(nine_slice) => nine_slice_instances(
+                        &self.sprites[sprite.index],
+                        &sprite.instance,
+                        nine_slice,
+                    ),
+                    None => vec![sprite.instance],
+                };
+                instances
+                    .into_iter()
+                    .map(move |instance| (layer, sprite.index, instance))
+            })
+            .collect::<Vec<_>>();
         let sprite_to_draw_count = sprite_data.len();
 
+        // Sprites on the same page are drawn together to not switch textures
+        sprite_data.sort_by_key(|(layer, sprite_idx, _)| {
+            (*layer, self.sprites[*sprite_idx].page, *sprite_idx)
+        });
+
         let now = Utc::now().naive_utc();
         self.first_render_ts.get_or_init(|| now);
         if let Some(last_ts) = self.last_render_ts {
@@ -427,63 +798,145 @@ impl Renderer {
         }
         self.last_render_ts = Some(now);
 
-        let view = output
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
-
         self.reserve_instance_buffer_for(sprite_to_draw_count as _);
+        self.reserve_shape_buffer_for(shape_vertices.len() as _);
+
+        let screenshot_texture = match &self.target {
+            RenderTarget::Window { config, .. } if self.screenshot_requested => Some(
+                Self::create_target_texture(&self.device, self.size, config.format),
+            ),
+            _ => None,
+        };
 
         let mut encoder = self
             .device()
             .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
 
-        sprite_data.sort_by(
-            |SpriteRenderData {
-                 index: sprite_idx_lhs,
-                 layer: layer_lhs,
-                 ..
-             },
-             SpriteRenderData {
-                 index: sprite_idx_rhs,
-                 layer: layer_rhs,
-                 ..
-             }| {
-                let layer_lhs = self.resolve_layer_ord(layer_lhs.as_ref());
-                let layer_rhs = self.resolve_layer_ord(layer_rhs.as_ref());
-
-                layer_lhs
-                    .cmp(&layer_rhs)
-                    .then(sprite_idx_lhs.cmp(sprite_idx_rhs))
-            },
+        // Shapes keep the order they were submitted in within their layer
+        let shape_data = shape_data
+            .into_iter()
+            .map(|shape| (self.resolve_layer_ord(shape.layer.as_ref()), shape.vertices))
+            .collect::<Vec<_>>();
+
+        let mut instances = Vec::with_capacity(sprite_to_draw_count);
+        let mut sprite_batches: Vec<(i32, SpriteIndex, Range<u32>)> = vec![];
+        for (layer, sprite_idx, instance) in sprite_data {
+            match sprite_batches.last_mut() {
+                Some((batch_layer, batch_sprite, batch_instances))
+                    if *batch_layer == layer && *batch_sprite == sprite_idx =>
+                {
+                    batch_instances.end += 1;
+                }
+                _ => {
+                    let first = instances.len() as u32;
+                    sprite_batches.push((layer, sprite_idx, first..first + 1));
+                }
+            }
+            instances.push(instance.raw());
+        }
+
+        // Within a layer the tilemaps are drawn first and the shapes last, the
+        // sort keeps the order of everything else
+        let mut commands =
+            Vec::with_capacity(tilemap_data.len() + sprite_batches.len() + shape_data.len());
+        for tilemap in tilemap_data {
+            let layer = self.resolve_layer_ord(tilemap.layer.as_ref());
+            commands.extend(tilemap.mesh.batches.iter().map(|(sprite_idx, instances)| {
+                (
+                    layer,
+                    DrawCommand::Sprites {
+                        sprite_idx: *sprite_idx,
+                        instances: instances.clone(),
+                        chunk: Some(tilemap.mesh.clone()),
+                    },
+                )
+            }));
+        }
+        commands.extend(
+            sprite_batches
+                .into_iter()
+                .map(|(layer, sprite_idx, instances)| {
+                    (
+                        layer,
+                        DrawCommand::Sprites {
+                            sprite_idx,
+                            instances,
+                            chunk: None,
+                        },
+                    )
+                }),
         );
+        commands.extend(
+            shape_data
+                .into_iter()
+                .map(|(layer, vertices)| (layer, DrawCommand::Shapes { vertices })),
+        );
+        commands.sort_by_key(|(layer, _)| *layer);
+
+        let camera_layers = self
+            .cameras
+            .iter()
+            .map(|render_camera| {
+                let resolve = |layers: &Vec<LayerIdentifier>| {
+                    layers
+                        .iter()
+                        .map(|layer| self.resolve_layer_ord(Some(layer)))
+                        .collect::<Vec<_>>()
+                };
+                match &render_camera.layers {
+                    LayerMask::All => ResolvedLayers::All,
+                    LayerMask::Only(layers) => ResolvedLayers::Only(resolve(layers)),
+                    LayerMask::Except(layers) => ResolvedLayers::Except(resolve(layers)),
+                }
+            })
+            .collect::<Vec<_>>();
 
         let Renderer {
             queue,
             render_pipeline,
+            shape_pipeline,
             vertex_buffer,
             instance_buffer,
+            shape_buffer,
             index_buffer,
             sprites,
-            cold_bind_group,
-            hot_bind_group,
+            atlas_pages,
+            cameras,
+            size,
+            multisampled_texture,
             #[cfg(feature = "egui")]
             egui_integration,
             #[cfg(feature = "egui")]
-            window,
+            target,
             #[cfg(feature = "egui")]
             device,
-            #[cfg(feature = "egui")]
-            config,
             clear_color,
             ..
         } = self;
 
-        {
+        // Windows can't be read from, so screenshots are drawn a second time
+        // into a texture of the same format
+        let screenshot_view = screenshot_texture
+            .as_ref()
+            .map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()));
+
+        let multisampled_view = multisampled_texture
+            .as_ref()
+            .map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()));
+
+        for view in std::iter::once(&view).chain(screenshot_view.as_ref()) {
+            // When multisampling everything is drawn into the multisampled
+            // texture and then resolved onto the actual target
+            let (view, resolve_target) = match &multisampled_view {
+                Some(multisampled_view) => (multisampled_view, Some(view)),
+                None => (view, None),
+            };
+
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: None,
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
+                    view,
+                    resolve_target,
                     ops: wgpu::Operations {
                         load: match clear_color {
                             Some(color) => wgpu::LoadOp::Clear(wgpu::Color {
@@ -500,69 +953,121 @@ impl Renderer {
                 depth_stencil_attachment: None,
             });
 
-            render_pass.set_pipeline(render_pipeline);
-
-            render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-            render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
-
-            render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-
-            render_pass.set_bind_group(0, cold_bind_group, &[]);
-            render_pass.set_bind_group(1, hot_bind_group, &[]);
-
-            let instances = sprite_data.into_iter().fold(
-                Vec::with_capacity(sprite_to_draw_count),
-                |mut instances,
-                 SpriteRenderData {
-                     index: sprite_idx,
-                     instance,
-                     ..
-                 }| {
-                    match instances.last_mut() {
-                        Some((current_sprite, _)) if *current_sprite != sprite_idx => {
-                            instances.push((sprite_idx, vec![instance]));
-                        }
-                        None => {
-                            instances.push((sprite_idx, vec![instance]));
+            // Every camera draws its layers into its part of the target
+            for (render_camera, layers) in cameras.iter().zip(camera_layers.iter()) {
+                let [x, y, width, height] = render_camera.viewport.pixels(*size);
+                if width == 0 || height == 0 {
+                    continue;
+                }
+                render_pass.set_viewport(x as f32, y as f32, width as f32, height as f32, 0., 1.);
+                render_pass.set_scissor_rect(x, y, width, height);
+
+                let [min, max] = render_camera.camera.visible_bounds();
+                let camera_bind_group = &render_camera.bind_group;
+
+                let mut bound_pipeline = None;
+                let mut bound_instances: Option<&wgpu::Buffer> = None;
+                let mut current_page = None;
+                for (_, command) in commands.iter().filter(|(layer, _)| layers.contains(*layer)) {
+                    match command {
+                        DrawCommand::Sprites {
+                            sprite_idx,
+                            instances,
+                            chunk,
+                        } => {
+                            // Tilemap chunks are only drawn by the cameras that see them
+                            if let Some(chunk) = chunk {
+                                let [chunk_min, chunk_max] = chunk.bounds;
+                                if chunk_min[0] > max.x
+                                    || chunk_max[0] < min.x
+                                    || chunk_min[1] > max.y
+                                    || chunk_max[1] < min.y
+                                {
+                                    continue;
+                                }
+                            }
+
+                            if bound_pipeline != Some(BoundPipeline::Sprites) {
+                                render_pass.set_pipeline(render_pipeline);
+                                render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                                render_pass.set_index_buffer(
+                                    index_buffer.slice(..),
+                                    wgpu::IndexFormat::Uint32,
+                                );
+                                render_pass.set_bind_group(1, camera_bind_group, &[]);
+                                bound_pipeline = Some(BoundPipeline::Sprites);
+                                bound_instances = None;
+                                current_page = None;
+                            }
+
+                            // Tilemap chunks keep their instances in their own buffers
+                            let instance_buffer = match chunk {
+                                Some(chunk) => &chunk.buffer,
+                                None => &*instance_buffer,
+                            };
+                            if !bound_instances
+                                .is_some_and(|bound| std::ptr::eq(bound, instance_buffer))
+                            {
+                                render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+                                bound_instances = Some(instance_buffer);
+                            }
+
+                            let page = sprites[*sprite_idx].page;
+                            if current_page != Some(page) {
+                                render_pass.set_bind_group(0, &atlas_pages[page].bind_group, &[]);
+                                current_page = Some(page);
+                            }
+
+                            render_pass.draw_indexed(
+                                sprites[*sprite_idx].indices(),
+                                0,
+                                instances.clone(),
+                            );
                         }
-                        Some((_, instances_of_this_sprite)) => {
-                            instances_of_this_sprite.push(instance);
+                        DrawCommand::Shapes { vertices } => {
+                            if bound_pipeline != Some(BoundPipeline::Shapes) {
+                                render_pass.set_pipeline(shape_pipeline);
+                                render_pass.set_vertex_buffer(0, shape_buffer.slice(..));
+                                render_pass.set_bind_group(0, camera_bind_group, &[]);
+                                bound_pipeline = Some(BoundPipeline::Shapes);
+                            }
+
+                            render_pass.draw(vertices.clone(), 0..1);
                         }
-                    };
-                    instances
-                },
-            );
-
-            let mut instances_processed = 0;
-            for (sprite_idx, instances) in instances.iter() {
-                render_pass.draw_indexed(
-                    sprites[*sprite_idx].indices(),
-                    0,
-                    instances_processed as _..(instances_processed + instances.len()) as _,
-                );
-
-                instances_processed += instances.len();
+                    }
+                }
             }
-
-            queue.write_buffer(
-                instance_buffer,
-                0,
-                bytemuck::cast_slice(
-                    &instances
-                        .into_iter()
-                        .flat_map(|(_, i)| i)
-                        .map(|i| i.raw())
-                        .collect::<Vec<_>>(),
-                ),
-            );
         }
 
+        queue.write_buffer(instance_buffer, 0, bytemuck::cast_slice(&instances));
+        queue.write_buffer(shape_buffer, 0, bytemuck::cast_slice(&shape_vertices));
+
         #[cfg(feature = "egui")]
-        egui_integration.end_frame(window, device, queue, config, &view, &mut encoder);
+        if let (Some(egui_integration), RenderTarget::Window { window, config, .. }) =
+            (egui_integration, &*target)
+        {
+            egui_integration.end_frame(window, device, queue, config, &view, &mut encoder);
+        }
 
         queue.submit(std::iter::once(encoder.finish()));
 
-        output.present();
+        let screenshot = if std::mem::take(&mut self.screenshot_requested) {
+            screenshot_texture
+                .as_ref()
+                .or(self.offscreen_texture())
+                .map(|texture| self.read_texture(texture))
+                .transpose()
+        } else {
+            Ok(None)
+        };
+
+        if let Some(output) = output {
+            output.present();
+        }
+
+        if let Some(screenshot) = screenshot? {
+            self.screenshot = Some(screenshot);
+        }
 
         Ok(())
     }
@@ -579,7 +1084,19 @@ impl Renderer {
         }
     }
 
-    pub(crate) fn resolve_layer_ord(&mut self, layer: Option<&LayerIdentifier>) -> i32 {
+    pub(crate) fn reserve_shape_buffer_for(&mut self, new_vertex_count: u64) {
+        if new_vertex_count > self.shape_vertex_count {
+            self.shape_vertex_count = new_vertex_count;
+            self.shape_buffer = self.device.create_buffer(&BufferDescriptor {
+                label: None,
+                size: self.shape_vertex_count * std::mem::size_of::<ShapeVertex>() as u64,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+        }
+    }
+
+    pub(crate) fn resolve_layer_ord(&self, layer: Option<&LayerIdentifier>) -> i32 {
         match layer {
             None => 0,
             Some(LayerIdentifier::Ordinal(c)) => *c,
@@ -594,19 +1111,42 @@ impl Renderer {
 pub struct ViewportProperties {
     pub camera: Camera,
     pub window_size: PhysicalSize<u32>,
+    /// The part of the window the camera draws into
+    pub rect: ViewportRect,
 }
 
 impl ViewportProperties {
+    /// The world position under the position in the window, in pixels.
     pub fn window_to_world(&self, window_position: impl Into<Vector2<u32>>) -> Vector2<f32> {
         let [x, y] = <[u32; 2]>::from(window_position.into()).map(|v| v as f32);
-        let [x, y] = [
-            (x / self.window_size.width as f32 * 2. - 1.)
-                * self.camera.size
-                * self.camera.aspect_ratio,
-            (-y / self.window_size.height as f32 * 2. + 1.) * self.camera.size,
-        ];
+        let [left, top, width, height] = self.rect_in_pixels();
+        self.camera
+            .ndc_to_world([(x - left) / width * 2. - 1., 1. - (y - top) / height * 2.].into())
+    }
 
-        [x, y].into()
+    /// Where the world position is drawn in the window, in pixels. Positions
+    /// outside of the window are not clamped.
+    pub fn world_to_window(&self, world_position: impl Into<Vector2<f32>>) -> Vector2<f32> {
+        let ndc = self.camera.world_to_ndc(world_position.into());
+        let [left, top, width, height] = self.rect_in_pixels();
+        [
+            left + (ndc.x + 1.) / 2. * width,
+            top + (1. - ndc.y) / 2. * height,
+        ]
+        .into()
+    }
+
+    fn rect_in_pixels(&self) -> [f32; 4] {
+        let (width, height) = (
+            self.window_size.width as f32,
+            self.window_size.height as f32,
+        );
+        [
+            self.rect.x * width,
+            self.rect.y * height,
+            self.rect.width * width,
+            self.rect.height * height,
+        ]
     }
 }
 
@@ -619,22 +1159,60 @@ impl Renderer {
         &self.queue
     }
 
-    pub fn window(&self) -> &Window {
-        &self.window
+    /// The window frames are presented onto, `None` for headless renderers.
+    pub fn window(&self) -> Option<&Window> {
+        match &self.target {
+            RenderTarget::Window { window, .. } => Some(window),
+            RenderTarget::Offscreen { .. } => None,
+        }
+    }
+
+    /// The texture frames are drawn into, `None` for windowed renderers.
+    pub fn offscreen_texture(&self) -> Option<&wgpu::Texture> {
+        match &self.target {
+            RenderTarget::Offscreen { texture } => Some(texture),
+            RenderTarget::Window { .. } => None,
+        }
     }
 
     pub fn size(&self) -> PhysicalSize<u32> {
         self.size
     }
 
+    /// The main camera.
     pub fn camera(&self) -> &Camera {
-        &self.camera
+        &self.cameras[0].camera
     }
 
+    pub fn named_camera(&self, name: &str) -> Option<&Camera> {
+        let idx = self.camera_index(name).ok()?;
+        Some(&self.cameras[idx].camera)
+    }
+
+    /// The names of the cameras in the order they are drawn.
+    pub fn camera_names(&self) -> impl Iterator<Item = &str> {
+        self.cameras
+            .iter()
+            .map(|render_camera| render_camera.name.as_str())
+    }
+
+    /// The viewport of the main camera.
     pub fn viewport(&self) -> ViewportProperties {
         ViewportProperties {
-            camera: self.camera,
-            window_size: self.window.inner_size(),
+            camera: self.cameras[0].camera,
+            window_size: self.size,
+            rect: self.cameras[0].viewport,
         }
     }
+
+    /// The viewport of the named camera, to convert positions in the window
+    /// into its world and back.
+    pub fn camera_viewport(&self, name: &str) -> Option<ViewportProperties> {
+        let idx = self.camera_index(name).ok()?;
+        Some(ViewportProperties {
+            camera: self.cameras[idx].camera,
+            window_size: self.size,
+            rect: self.cameras[idx].viewport,
+        })
+    }
 }
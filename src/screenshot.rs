@@ -0,0 +1,100 @@
+use std::sync::mpsc;
+
+use image::RgbaImage;
+
+use crate::{renderer::RenderTarget, Renderer, TangerineError};
+
+impl Renderer {
+    /// Copy the last frame drawn by a headless renderer back to the CPU.
+    /// Returns `None` for renderers presenting onto a window, use
+    /// `.request_screenshot()` for those instead.
+    pub fn read_frame(&self) -> Result<Option<RgbaImage>, TangerineError> {
+        match &self.target {
+            RenderTarget::Offscreen { texture } => self.read_texture(texture).map(Some),
+            RenderTarget::Window { .. } => Ok(None),
+        }
+    }
+
+    /// Capture the next frame drawn by `.draw_frame(..)`, it can be
+    /// retrieved with `.take_screenshot()` after it is drawn.
+    pub fn request_screenshot(&mut self) {
+        self.screenshot_requested = true;
+    }
+
+    /// Take the frame captured after `.request_screenshot()`, if it was drawn.
+    pub fn take_screenshot(&mut self) -> Option<RgbaImage> {
+        self.screenshot.take()
+    }
+
+    /// Copy the contents of a texture into a mapped buffer and decode it.
+    pub(crate) fn read_texture(
+        &self,
+        texture: &wgpu::Texture,
+    ) -> Result<RgbaImage, TangerineError> {
+        let (width, height) = (texture.width(), texture.height());
+
+        // Rows of the copied texture have to be aligned in the buffer
+        let unpadded_bytes_per_row = 4 * width;
+        let padded_bytes_per_row = unpadded_bytes_per_row
+            .div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (padded_bytes_per_row * height) as _,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            texture.size(),
+        );
+
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            // The receiver only goes away once the mapping was waited for
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .unwrap_or(Err(wgpu::BufferAsyncError))
+            .map_err(TangerineError::BufferMap)?;
+
+        let mut pixels = Vec::with_capacity((unpadded_bytes_per_row * height) as _);
+        for row in slice
+            .get_mapped_range()
+            .chunks_exact(padded_bytes_per_row as _)
+        {
+            pixels.extend_from_slice(&row[..unpadded_bytes_per_row as _]);
+        }
+        buffer.unmap();
+
+        if matches!(
+            texture.format(),
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        ) {
+            for [b, _, r, _] in pixels.iter_mut().array_chunks::<4>() {
+                std::mem::swap(b, r);
+            }
+        }
+
+        Ok(RgbaImage::from_raw(width, height, pixels).unwrap())
+    }
+}
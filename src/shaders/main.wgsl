@@ -1,6 +1,9 @@
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) texture_coordinates: vec2<f32>,
+    @location(2) corner: vec2<f32>,
+    @location(3) quad_size: vec2<f32>,
+    @location(4) tex_size: vec2<f32>,
 };
 
 struct VertexOutput {
@@ -10,10 +13,13 @@ struct VertexOutput {
 }
 
 struct InstanceInput {
-    @location(2) pos: vec3<f32>,
-    @location(3) scale: vec2<f32>,
-    @location(4) angle: f32,
-    @location(5) color: vec4<f32>
+    @location(5) pos: vec3<f32>,
+    @location(6) scale: vec2<f32>,
+    @location(7) angle: f32,
+    @location(8) color: vec4<f32>,
+    @location(9) sub_rect: vec4<f32>,
+    @location(10) pivot: vec2<f32>,
+    @location(11) flags: u32,
 };
 
 struct Camera {
@@ -43,9 +49,23 @@ fn vs_main(
             -sinn, coss, 0.,
             0., 0., 1.);
 
-    out.tex_coords = model.texture_coordinates;
+    // Move the corner onto the edge of the sub-rect, the positions grow upwards
+    // while the texture coordinates grow downwards
+    let corner = instance.sub_rect.xy + model.corner * instance.sub_rect.zw;
+    let offset = (corner - model.corner) * vec2<f32>(1.0, -1.0) * model.quad_size;
+
+    // The instance's pivot replaces the one the mesh was built around
+    var base = model.position;
+    if (instance.flags & 4u) != 0u {
+        base = vec3<f32>((model.corner - instance.pivot) * vec2<f32>(1.0, -1.0) * model.quad_size, model.position.z);
+    }
+    let position = base + vec3<f32>(offset, 0.0);
+
+    let flipped = select(corner, 1.0 - corner, vec2<bool>((instance.flags & 1u) != 0u, (instance.flags & 2u) != 0u));
+
+    out.tex_coords = model.texture_coordinates + (flipped - model.corner) * model.tex_size;
     out.tint = instance.color;
-    out.clip_position = camera.view * vec4<f32>((instance.pos + rotor * model.position * vec3<f32>(instance.scale, 0.0)), 1.0);
+    out.clip_position = camera.view * vec4<f32>((instance.pos + rotor * position * vec3<f32>(instance.scale, 0.0)), 1.0);
     return out;
 }
 
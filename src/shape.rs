@@ -0,0 +1,289 @@
+use std::{alloc::Allocator, f32::consts::TAU, ops::Range};
+
+use cint::EncodedSrgb;
+use mint::{Vector2, Vector3};
+
+use crate::{vertex::ShapeVertex, FrameBuilder, LayerIdentifier};
+
+/// Segments used for circles unless specified otherwise.
+const DEFAULT_CIRCLE_SEGMENTS: u32 = 32;
+
+/// A shape submitted to a frame, its vertices are already in world space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeRenderData {
+    pub layer: Option<LayerIdentifier>,
+    /// The range of the shape's vertices in the frame
+    pub vertices: Range<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    Rect {
+        size: Vector2<f32>,
+    },
+    Circle {
+        radius: f32,
+        segments: u32,
+    },
+    Line {
+        from: Vector2<f32>,
+        to: Vector2<f32>,
+        thickness: f32,
+    },
+    Polygon {
+        points: Vec<Vector2<f32>>,
+    },
+}
+
+impl<A: Allocator> FrameBuilder<A> {
+    /// Draw a rectangle centered on its position.
+    pub fn draw_rect<'me>(&'me mut self, size: impl Into<Vector2<f32>>) -> DrawShape<'me, A> {
+        DrawShape::new(self, Shape::Rect { size: size.into() })
+    }
+
+    /// Draw a circle centered on its position.
+    pub fn draw_circle<'me>(&'me mut self, radius: f32) -> DrawShape<'me, A> {
+        DrawShape::new(
+            self,
+            Shape::Circle {
+                radius,
+                segments: DEFAULT_CIRCLE_SEGMENTS,
+            },
+        )
+    }
+
+    /// Draw a line between the points, offset by the position. The line is
+    /// drawn with the fill colour.
+    pub fn draw_line<'me>(
+        &'me mut self,
+        from: impl Into<Vector2<f32>>,
+        to: impl Into<Vector2<f32>>,
+        thickness: f32,
+    ) -> DrawShape<'me, A> {
+        DrawShape::new(
+            self,
+            Shape::Line {
+                from: from.into(),
+                to: to.into(),
+                thickness,
+            },
+        )
+    }
+
+    /// Draw a convex polygon through the points, offset by the position.
+    pub fn draw_polygon<'me>(
+        &'me mut self,
+        points: impl IntoIterator<Item = impl Into<Vector2<f32>>>,
+    ) -> DrawShape<'me, A> {
+        DrawShape::new(
+            self,
+            Shape::Polygon {
+                points: points.into_iter().map(Into::into).collect(),
+            },
+        )
+    }
+}
+
+#[must_use]
+pub struct DrawShape<'builder, A: Allocator> {
+    builder: &'builder mut FrameBuilder<A>,
+    shape: Shape,
+    layer: Option<LayerIdentifier>,
+    position: Vector3<f32>,
+    rotation_rad: f32,
+    fill: Option<EncodedSrgb<u8>>,
+    outline: Option<(EncodedSrgb<u8>, f32)>,
+    opacity: f32,
+}
+
+impl<'builder, A: Allocator> DrawShape<'builder, A> {
+    fn new(builder: &'builder mut FrameBuilder<A>, shape: Shape) -> Self {
+        Self {
+            builder,
+            shape,
+            layer: None,
+            position: [0.; 3].into(),
+            rotation_rad: 0.,
+            fill: Some([0xFFu8; 3].into()),
+            outline: None,
+            opacity: 1.,
+        }
+    }
+
+    pub fn layer(self, layer: impl Into<LayerIdentifier>) -> Self {
+        Self {
+            layer: Some(layer.into()),
+            ..self
+        }
+    }
+
+    pub fn pos(mut self, pos: impl Into<Vector3<f32>>) -> Self {
+        self.position = pos.into();
+        self
+    }
+
+    /// Rotation around the position in rad, matching `DrawSprite::rotate(..)`.
+    pub fn rotate(mut self, rad: f32) -> Self {
+        self.rotation_rad = rad;
+        self
+    }
+
+    /// Colour of the inside of the shape, white by default.
+    pub fn fill(mut self, color: impl Into<EncodedSrgb<u8>>) -> Self {
+        self.fill = Some(color.into());
+        self
+    }
+
+    /// Leave the inside of the shape empty, only drawing its outline.
+    pub fn no_fill(mut self) -> Self {
+        self.fill = None;
+        self
+    }
+
+    /// Draw the edges of the shape with the colour, centered on the edges.
+    pub fn outline(mut self, color: impl Into<EncodedSrgb<u8>>, thickness: f32) -> Self {
+        self.outline = Some((color.into(), thickness));
+        self
+    }
+
+    pub fn segments(mut self, count: u32) -> Self {
+        if let Shape::Circle { segments, .. } = &mut self.shape {
+            *segments = count.max(3);
+        }
+        self
+    }
+
+    pub fn opacity(mut self, opacity: impl Into<f32>) -> Self {
+        self.opacity = opacity.into();
+        self
+    }
+
+    pub fn done(self) -> &'builder mut FrameBuilder<A> {
+        let mut triangles: Vec<([f32; 2], [f32; 4])> = vec![];
+
+        match &self.shape {
+            Shape::Line {
+                from,
+                to,
+                thickness,
+            } => {
+                if let Some(fill) = self.fill {
+                    let color = shape_color(fill, self.opacity);
+                    triangles.extend(
+                        line_triangles([from.x, from.y], [to.x, to.y], *thickness)
+                            .into_iter()
+                            .map(|point| (point, color)),
+                    );
+                }
+            }
+            shape => {
+                let points = match shape {
+                    Shape::Rect { size } => {
+                        let [x, y] = [size.x / 2., size.y / 2.];
+                        vec![[-x, -y], [x, -y], [x, y], [-x, y]]
+                    }
+                    Shape::Circle { radius, segments } => (0..*segments)
+                        .map(|segment| {
+                            let angle = segment as f32 / *segments as f32 * TAU;
+                            [radius * angle.cos(), radius * angle.sin()]
+                        })
+                        .collect(),
+                    Shape::Polygon { points } => points.iter().map(|p| [p.x, p.y]).collect(),
+                    Shape::Line { .. } => unreachable!(),
+                };
+
+                if let Some(fill) = self.fill {
+                    let color = shape_color(fill, self.opacity);
+                    for idx in 1..points.len().saturating_sub(1) {
+                        triangles.extend(
+                            [points[0], points[idx], points[idx + 1]].map(|point| (point, color)),
+                        );
+                    }
+                }
+                if let Some((color, thickness)) = self.outline {
+                    let color = shape_color(color, self.opacity);
+                    triangles.extend(
+                        outline_triangles(&points, thickness)
+                            .into_iter()
+                            .map(|point| (point, color)),
+                    );
+                }
+            }
+        }
+
+        // Rotated the same way as the sprites are
+        let (sin, cos) = self.rotation_rad.sin_cos();
+        let position = self.position;
+        self.builder.submit_shape(
+            self.layer,
+            triangles.into_iter().map(|([x, y], color)| ShapeVertex {
+                position: [
+                    position.x + x * cos - y * sin,
+                    position.y + x * sin + y * cos,
+                    position.z,
+                ],
+                color,
+            }),
+        );
+
+        self.builder
+    }
+}
+
+/// The colour as passed to the shader, the same as the tint of sprites.
+fn shape_color(color: EncodedSrgb<u8>, opacity: f32) -> [f32; 4] {
+    let [r, g, b] = [color.r, color.g, color.b].map(|component| component as f32 / 255.);
+    [r, g, b, opacity.clamp(0., 1.)]
+}
+
+fn line_triangles(from: [f32; 2], to: [f32; 2], thickness: f32) -> [[f32; 2]; 6] {
+    let direction = [to[0] - from[0], to[1] - from[1]];
+    let length = direction[0].hypot(direction[1]).max(f32::EPSILON);
+    let normal = [
+        -direction[1] / length * thickness / 2.,
+        direction[0] / length * thickness / 2.,
+    ];
+
+    let a = [from[0] + normal[0], from[1] + normal[1]];
+    let b = [from[0] - normal[0], from[1] - normal[1]];
+    let c = [to[0] - normal[0], to[1] - normal[1]];
+    let d = [to[0] + normal[0], to[1] + normal[1]];
+    [a, b, c, a, c, d]
+}
+
+/// A ring of mitered quads along the closed outline of the points.
+fn outline_triangles(points: &[[f32; 2]], thickness: f32) -> Vec<[f32; 2]> {
+    let normal = |from: [f32; 2], to: [f32; 2]| {
+        let direction = [to[0] - from[0], to[1] - from[1]];
+        let length = direction[0].hypot(direction[1]).max(f32::EPSILON);
+        [-direction[1] / length, direction[0] / length]
+    };
+
+    let count = points.len();
+    let corners = (0..count)
+        .map(|idx| {
+            let point = points[idx];
+            let previous = normal(points[(idx + count - 1) % count], point);
+            let next = normal(point, points[(idx + 1) % count]);
+
+            let miter = [previous[0] + next[0], previous[1] + next[1]];
+            let miter_length = miter[0].hypot(miter[1]).max(f32::EPSILON);
+            let miter = [miter[0] / miter_length, miter[1] / miter_length];
+            // Sharp corners would make the miter arbitrarily long
+            let scale = thickness / 2. / (miter[0] * next[0] + miter[1] * next[1]).max(0.25);
+
+            (
+                [point[0] + miter[0] * scale, point[1] + miter[1] * scale],
+                [point[0] - miter[0] * scale, point[1] - miter[1] * scale],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut triangles = vec![];
+    for idx in 0..count {
+        let (outer_a, inner_a) = corners[idx];
+        let (outer_b, inner_b) = corners[(idx + 1) % count];
+        triangles.extend([outer_a, inner_a, inner_b, outer_a, inner_b, outer_b]);
+    }
+    triangles
+}
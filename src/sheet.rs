@@ -0,0 +1,173 @@
+use image::{imageops, DynamicImage};
+use serde::{Deserialize, Serialize};
+
+use crate::{AtlasBuilder, SpriteIndex, SpriteLoadOptions, TangerineError};
+
+/// A rectangle of pixels inside of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct SpriteRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl SpriteRect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+impl From<[u32; 4]> for SpriteRect {
+    fn from([x, y, width, height]: [u32; 4]) -> Self {
+        Self::new(x, y, width, height)
+    }
+}
+
+/// How a sprite sheet is sliced into separate sprites.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SpriteSheet {
+    /// Cells of the same size laid out in rows, sliced left to right and
+    /// then top to bottom.
+    Grid {
+        cell_size: [u32; 2],
+        /// Pixels between the top left corner of the image and the first cell
+        margin: [u32; 2],
+        /// Pixels between the neighbouring cells
+        spacing: [u32; 2],
+        /// Only slice the first cells, for sheets with an incomplete last row
+        count: Option<usize>,
+    },
+    /// Arbitrary rectangles, sliced in the order they are listed.
+    Rects(Vec<SpriteRect>),
+}
+
+impl SpriteSheet {
+    /// A grid of cells without any margin or spacing between them.
+    pub fn grid(cell_width: u32, cell_height: u32) -> Self {
+        Self::Grid {
+            cell_size: [cell_width, cell_height],
+            margin: [0; 2],
+            spacing: [0; 2],
+            count: None,
+        }
+    }
+
+    pub fn rects(rects: impl IntoIterator<Item = impl Into<SpriteRect>>) -> Self {
+        Self::Rects(rects.into_iter().map(Into::into).collect())
+    }
+
+    /// Set the margin of the grid, does nothing for explicit rects.
+    pub fn margin(mut self, x: u32, y: u32) -> Self {
+        if let Self::Grid { margin, .. } = &mut self {
+            *margin = [x, y];
+        }
+        self
+    }
+
+    /// Set the spacing of the grid, does nothing for explicit rects.
+    pub fn spacing(mut self, x: u32, y: u32) -> Self {
+        if let Self::Grid { spacing, .. } = &mut self {
+            *spacing = [x, y];
+        }
+        self
+    }
+
+    /// Limit the amount of cells of the grid, does nothing for explicit rects.
+    pub fn count(mut self, cells: usize) -> Self {
+        if let Self::Grid { count, .. } = &mut self {
+            *count = Some(cells);
+        }
+        self
+    }
+
+    /// The rectangles of every sprite of a sheet with the given size. A grid
+    /// only has the cells that fit completely, and none if they are empty.
+    pub fn slices(&self, width: u32, height: u32) -> Vec<SpriteRect> {
+        match self {
+            SpriteSheet::Rects(rects) => rects.clone(),
+            SpriteSheet::Grid {
+                cell_size: [cell_width, cell_height],
+                margin: [margin_x, margin_y],
+                spacing: [spacing_x, spacing_y],
+                count,
+            } => {
+                let cells_along = |size: u32, margin: u32, cell: u32, spacing: u32| {
+                    if cell == 0 {
+                        return 0;
+                    }
+                    (size.saturating_sub(margin) + spacing) / (cell + spacing)
+                };
+                let columns = cells_along(width, *margin_x, *cell_width, *spacing_x);
+                let rows = cells_along(height, *margin_y, *cell_height, *spacing_y);
+
+                (0..rows)
+                    .flat_map(|row| (0..columns).map(move |column| (column, row)))
+                    .map(|(column, row)| SpriteRect {
+                        x: margin_x + column * (cell_width + spacing_x),
+                        y: margin_y + row * (cell_height + spacing_y),
+                        width: *cell_width,
+                        height: *cell_height,
+                    })
+                    .take(count.unwrap_or(usize::MAX))
+                    .collect()
+            }
+        }
+    }
+}
+
+impl<'me, const N: usize> AtlasBuilder<'me, N> {
+    /// Slice the image into sprites, each of them dispatched dynamically like
+    /// with `.add_sprite_dynamically(..)`. The indices follow the order of the
+    /// slices. Fails if a slice is empty or reaches outside of the image.
+    pub fn add_sprite_sheet(
+        self,
+        image: impl Into<DynamicImage>,
+        sheet: &SpriteSheet,
+    ) -> Result<(Self, Vec<SpriteIndex>), TangerineError> {
+        let options = self.default_options;
+        self.add_sprite_sheet_advanced(image, sheet, options)
+    }
+
+    /// Slice the image into sprites loaded with options not activated by default.
+    pub fn add_sprite_sheet_advanced(
+        mut self,
+        image: impl Into<DynamicImage>,
+        sheet: &SpriteSheet,
+        options: impl Into<SpriteLoadOptions>,
+    ) -> Result<(Self, Vec<SpriteIndex>), TangerineError> {
+        let options = options.into();
+        let image = crate::atlas::load_image(image.into(), &options);
+        let slices = sheet.slices(image.width(), image.height());
+
+        // Checked up front to not queue a part of the sheet
+        let within = |position: u32, size: u32, sheet_size: u32| {
+            size > 0
+                && position
+                    .checked_add(size)
+                    .is_some_and(|end| end <= sheet_size)
+        };
+        if let Some(slice) = slices.iter().find(|rect| {
+            !within(rect.x, rect.width, image.width())
+                || !within(rect.y, rect.height, image.height())
+        }) {
+            return Err(TangerineError::InvalidSlice {
+                slice: *slice,
+                sheet_size: [image.width(), image.height()],
+            });
+        }
+
+        let mut indices = vec![];
+        for rect in slices {
+            let slice = imageops::crop_imm(&image, rect.x, rect.y, rect.width, rect.height);
+            indices.push(self.push_sprite(slice.to_image(), options)?);
+        }
+
+        Ok((self, indices))
+    }
+}
@@ -1,18 +1,69 @@
 use std::ops::Range;
 
+use serde::{Deserialize, Serialize};
+
 pub type SpriteIndex = usize;
 
 /// Options of the sprite when it's loaded into the sprite atlas and stitched.
-#[derive(Debug, Clone, Copy, PartialEq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SpriteLoadOptions {
     /// Premultiplied images are unpremultiplied before storage.
     /// This flag specifies if it should be done to a sprite.
     pub premultiplied: bool,
+    /// Transparent pixels kept between the sprite and its neighbours in the atlas.
+    pub padding: u32,
+    /// How many times the edge pixels are repeated around the sprite, so that
+    /// filtering near the edges doesn't sample the neighbours.
+    pub extrude: u32,
+    /// The point the sprite is positioned, rotated and scaled around, as
+    /// normalized coordinates with the origin at the top left.
+    pub pivot: [f32; 2],
+    /// How many pixels of the sprite span a world unit, replacing the
+    /// renderer's setting.
+    pub pixels_per_unit: Option<f32>,
+    /// Borders of the sprite in pixels as `[left, top, right, bottom]`. When
+    /// drawn as a nine-slice the corners keep their size while the edges and
+    /// the centre fill the rest.
+    pub nine_slice: Option<[u32; 4]>,
+}
+
+impl Default for SpriteLoadOptions {
+    fn default() -> Self {
+        Self {
+            premultiplied: false,
+            padding: 0,
+            extrude: 0,
+            pivot: [0.5; 2],
+            pixels_per_unit: None,
+            nine_slice: None,
+        }
+    }
+}
+
+impl SpriteLoadOptions {
+    /// Size of the sprite's image once its edges are extruded.
+    pub(crate) fn extruded_size(&self, width: u32, height: u32) -> [u32; 2] {
+        [width + 2 * self.extrude, height + 2 * self.extrude]
+    }
+
+    /// How much space the sprite takes in the atlas, including the padding.
+    pub(crate) fn footprint(&self, width: u32, height: u32) -> [u32; 2] {
+        self.extruded_size(width, height)
+            .map(|dimension| dimension + self.padding)
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) struct SpriteDrawData {
     pub sprite_index_range: (u32, u32),
+    /// The atlas page the sprite's texture is on
+    pub page: usize,
+    /// Size of the sprite's mesh in world units
+    pub quad_size: [f32; 2],
+    pub pivot: [f32; 2],
+    /// Nine-slice borders normalized to the size of the sprite
+    pub borders: [f32; 4],
 }
 
 impl SpriteDrawData {
@@ -6,6 +6,7 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
+use chrono::Local;
 use hashbrown::HashSet;
 use mint::Vector2;
 use smallvec::{smallvec, SmallVec};
@@ -16,7 +17,7 @@ use winit::{
     window::WindowBuilder,
 };
 
-use crate::{frame::FrameBuilder, Renderer};
+use crate::{frame::FrameBuilder, Renderer, RendererBuilder, TangerineError};
 
 /// Standalone renderer that instead of taking ownership of an existing window creates its own.
 pub struct StandaloneRenderer {
@@ -24,6 +25,9 @@ pub struct StandaloneRenderer {
     renderer: Renderer,
     /// Event loop in which the `.run(..)` function is ran.
     event_loop: EventLoop<()>,
+    /// Key that saves a PNG screenshot of the next frame into the working
+    /// directory, `None` by default. The key still reaches the game's input.
+    pub screenshot_key: Option<VirtualKeyCode>,
 }
 
 /// All the input gathered by the [`StandaloneRenderer`] since last frame.
@@ -54,16 +58,35 @@ impl Default for StandaloneInputState {
 impl StandaloneRenderer {
     /// Create a new standalone renderer with the provided window title.
     pub fn new(window_title: impl Into<String>) -> Self {
+        Self::try_new(window_title).unwrap()
+    }
+
+    /// Same as `StandaloneRenderer::new` but fails instead of panicking when
+    /// the window or the GPU objects can't be created.
+    pub fn try_new(window_title: impl Into<String>) -> Result<Self, TangerineError> {
+        Self::try_with_builder(window_title, RendererBuilder::default())
+    }
+
+    /// Create a new standalone renderer configured by the builder.
+    pub fn with_builder(window_title: impl Into<String>, builder: RendererBuilder) -> Self {
+        Self::try_with_builder(window_title, builder).unwrap()
+    }
+
+    /// Same as `StandaloneRenderer::with_builder` but fails instead of panicking.
+    pub fn try_with_builder(
+        window_title: impl Into<String>,
+        builder: RendererBuilder,
+    ) -> Result<Self, TangerineError> {
         let event_loop = EventLoop::new();
         let window = WindowBuilder::new()
             .with_title(window_title)
-            .build(&event_loop)
-            .unwrap();
+            .build(&event_loop)?;
 
-        Self {
-            renderer: Renderer::from(window),
+        Ok(Self {
+            renderer: builder.build(window)?,
             event_loop,
-        }
+            screenshot_key: None,
+        })
     }
 }
 pub trait StandaloneDrawCallback<E: Error = Infallible, A: Allocator = Global> =
@@ -85,11 +108,21 @@ impl StandaloneRenderer {
     pub fn run<E: Error>(
         self,
         mut draw_callback: impl StandaloneDrawCallback<E> + 'static,
+    ) -> Result<(), E> {
+        self.run_with_renderer(move |_, frame_builder, input| draw_callback(frame_builder, input))
+    }
+
+    /// Same as `StandaloneRenderer::run` but the callback also gets the
+    /// renderer, to add sprites or change the window between frames.
+    pub fn run_with_renderer<E: Error>(
+        self,
+        mut draw_callback: impl FnMut(&mut Renderer, &mut FrameBuilder, &StandaloneInputState) -> Result<(), E>
+            + 'static,
     ) -> Result<(), E> {
         let StandaloneRenderer {
             mut event_loop,
             mut renderer,
-            ..
+            screenshot_key,
         } = self;
 
         let mut gathered_input = StandaloneInputState {
@@ -106,46 +139,48 @@ impl StandaloneRenderer {
             let mut keys_pressed: HashSet<VirtualKeyCode> = Default::default();
 
             move |event, _, control_flow| {
-                let window = renderer.window();
+                let window_id = renderer
+                    .window()
+                    .expect("standalone renderers always present onto a window")
+                    .id();
 
                 match event {
                     Event::WindowEvent {
                         ref event,
-                        window_id,
-                    } if window_id == window.id() => {
-                        if !renderer.input(event) {
-                            match event {
-                                WindowEvent::KeyboardInput {
-                                    input:
-                                        KeyboardInput {
-                                            scancode,
-                                            state,
-                                            virtual_keycode: Some(keycode),
-                                            ..
-                                        },
+                        window_id: event_window_id,
+                    } if event_window_id == window_id && !renderer.input(event) => match event {
+                        WindowEvent::KeyboardInput {
+                            input:
+                                KeyboardInput {
+                                    scancode,
+                                    state,
+                                    virtual_keycode: Some(keycode),
                                     ..
-                                } => match state {
-                                    ElementState::Pressed if !keys_pressed.contains(keycode) => {
-                                        gathered_input.pressed_keys.push((*scancode, *keycode));
-                                        keys_pressed.insert(*keycode);
-                                    }
-                                    ElementState::Released => {
-                                        gathered_input.released_keys.push((*scancode, *keycode));
-                                        keys_pressed.remove(keycode);
-                                    }
-                                    _ => {}
                                 },
-                                WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
-                                WindowEvent::CursorMoved { position, .. } => {
-                                    gathered_input.cursor_pos =
-                                        [position.x as u32, position.y as u32].into();
+                            ..
+                        } => match state {
+                            ElementState::Pressed if !keys_pressed.contains(keycode) => {
+                                if Some(*keycode) == screenshot_key {
+                                    renderer.request_screenshot();
                                 }
-                                _ => {}
+                                gathered_input.pressed_keys.push((*scancode, *keycode));
+                                keys_pressed.insert(*keycode);
                             }
+                            ElementState::Released => {
+                                gathered_input.released_keys.push((*scancode, *keycode));
+                                keys_pressed.remove(keycode);
+                            }
+                            _ => {}
+                        },
+                        WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
+                        WindowEvent::CursorMoved { position, .. } => {
+                            gathered_input.cursor_pos =
+                                [position.x as u32, position.y as u32].into();
                         }
-                    }
+                        _ => {}
+                    },
 
-                    Event::RedrawRequested(window_id) if window_id == renderer.window().id() => {
+                    Event::RedrawRequested(event_window_id) if event_window_id == window_id => {
                         gathered_input.delta_time_secs = renderer
                             .delta_time
                             .to_owned()
@@ -154,10 +189,11 @@ impl StandaloneRenderer {
                             as f32
                             * (10e-9);
 
-                        let mut frame_builder =
-                            FrameBuilder::new_global(renderer.viewport());
+                        let mut frame_builder = FrameBuilder::new_global(renderer.viewport());
 
-                        if let Err(err) = draw_callback(&mut frame_builder, &gathered_input) {
+                        if let Err(err) =
+                            draw_callback(&mut renderer, &mut frame_builder, &gathered_input)
+                        {
                             *error_return = err.into();
                             *control_flow = ControlFlow::Exit;
                             return;
@@ -168,16 +204,29 @@ impl StandaloneRenderer {
 
                         match renderer.draw_frame(frame_builder.finalize()) {
                             Ok(_) => {}
-                            Err(wgpu::SurfaceError::Lost) => renderer.resize(renderer.size()),
-                            Err(wgpu::SurfaceError::OutOfMemory) => {
+                            Err(TangerineError::Surface(wgpu::SurfaceError::Lost)) => {
+                                renderer.resize(renderer.size())
+                            }
+                            Err(TangerineError::Surface(wgpu::SurfaceError::OutOfMemory)) => {
                                 *control_flow = ControlFlow::Exit
                             }
                             Err(e) => eprintln!("{:?}", e),
                         }
+
+                        if let Some(screenshot) = renderer.take_screenshot() {
+                            let path = Local::now()
+                                .format("screenshot-%Y%m%d-%H%M%S%.3f.png")
+                                .to_string();
+                            if let Err(e) = screenshot.save(&path) {
+                                eprintln!("{:?}", e);
+                            }
+                        }
                     }
 
                     Event::MainEventsCleared => {
-                        renderer.window().request_redraw();
+                        if let Some(window) = renderer.window() {
+                            window.request_redraw();
+                        }
                     }
 
                     _ => {}
@@ -0,0 +1,610 @@
+use std::{
+    alloc::Allocator,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use cint::EncodedSrgb;
+use hashbrown::HashMap;
+use image::{imageops, DynamicImage};
+use mint::Vector3;
+
+use crate::{
+    AtlasBuilder, FrameBuilder, LayerIdentifier, SpriteIndex, SpriteInstance, SpriteLoadOptions,
+    SpriteRect, SpriteSheet, SpriteTransform, TangerineError,
+};
+
+/// A single character of a font, measured in the font's pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glyph {
+    /// The glyph's sprite, `None` for the characters without any pixels
+    pub sprite: Option<SpriteIndex>,
+    /// Offset of the sprite's top left corner from the pen on the top of the line
+    pub offset: [f32; 2],
+    /// How far the pen moves after the glyph
+    pub advance: f32,
+}
+
+/// Source of the glyphs text is laid out with.
+///
+/// The sprites of the glyphs are expected to be loaded with the top left
+/// pivot and the line height as their pixels per unit, so that a line of
+/// text is one unit tall.
+pub trait Font {
+    fn glyph(&self, character: char) -> Option<&Glyph>;
+
+    /// Additional advance between the two characters.
+    fn kerning(&self, _left: char, _right: char) -> f32 {
+        0.
+    }
+
+    /// Distance between the tops of two lines in pixels.
+    fn line_height(&self) -> f32;
+}
+
+/// A font whose glyphs are all loaded into the atlas up front.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BitmapFont {
+    pub(crate) glyphs: HashMap<char, Glyph>,
+    pub(crate) kerning: HashMap<(char, char), f32>,
+    pub(crate) line_height: f32,
+}
+
+impl Font for BitmapFont {
+    fn glyph(&self, character: char) -> Option<&Glyph> {
+        self.glyphs.get(&character)
+    }
+
+    fn kerning(&self, left: char, right: char) -> f32 {
+        self.kerning.get(&(left, right)).copied().unwrap_or(0.)
+    }
+
+    fn line_height(&self) -> f32 {
+        self.line_height
+    }
+}
+
+/// A font in the text format of AngelCode's BMFont.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BmFont {
+    pub line_height: f32,
+    /// Paths to the images of the pages, relative to the `.fnt` file when loaded with `.load(..)`
+    pub pages: Vec<PathBuf>,
+    pub chars: Vec<BmFontChar>,
+    /// Pairs of characters and the adjustment of the advance between them
+    pub kernings: Vec<(char, char, f32)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BmFontChar {
+    pub character: char,
+    pub page: usize,
+    /// Where the character is in its page
+    pub rect: SpriteRect,
+    pub offset: [f32; 2],
+    pub advance: f32,
+}
+
+impl BmFont {
+    /// Parse the text format of a `.fnt` file.
+    pub fn parse(fnt: &str) -> Result<Self, TangerineError> {
+        let mut font = BmFont::default();
+        // Bounds the page ids, so that a bogus one can't allocate gigabytes
+        let mut page_count = MAX_FNT_PAGES;
+
+        for line in fnt.lines().filter(|line| !line.trim().is_empty()) {
+            let (tag, attributes) = parse_fnt_line(line);
+            let number = |key: &str| fnt_number::<f32>(&attributes, tag, key);
+
+            match tag {
+                "common" => {
+                    font.line_height = number("lineHeight")?;
+                    if attributes.contains_key("pages") {
+                        page_count =
+                            fnt_number::<usize>(&attributes, tag, "pages")?.min(MAX_FNT_PAGES);
+                    }
+                }
+                "page" => {
+                    let id = fnt_number::<usize>(&attributes, tag, "id")?;
+                    let file = attributes
+                        .get("file")
+                        .ok_or_else(|| invalid_fnt(tag, "file"))?;
+                    if id >= page_count {
+                        return Err(TangerineError::InvalidFont(format!(
+                            "page {id} is beyond the {page_count} pages of the font"
+                        )));
+                    }
+                    if font.pages.len() <= id {
+                        font.pages.resize(id + 1, PathBuf::new());
+                    }
+                    font.pages[id] = file.into();
+                }
+                "char" => font.chars.push(BmFontChar {
+                    character: fnt_char(&attributes, tag, "id")?,
+                    page: fnt_number(&attributes, tag, "page").unwrap_or(0),
+                    rect: SpriteRect::new(
+                        fnt_number(&attributes, tag, "x")?,
+                        fnt_number(&attributes, tag, "y")?,
+                        fnt_number(&attributes, tag, "width")?,
+                        fnt_number(&attributes, tag, "height")?,
+                    ),
+                    offset: [number("xoffset")?, number("yoffset")?],
+                    advance: number("xadvance")?,
+                }),
+                "kerning" => font.kernings.push((
+                    fnt_char(&attributes, tag, "first")?,
+                    fnt_char(&attributes, tag, "second")?,
+                    number("amount")?,
+                )),
+                _ => {}
+            }
+        }
+
+        if font.line_height <= 0. {
+            return Err(TangerineError::InvalidFont(
+                "the font has no line height".to_string(),
+            ));
+        }
+
+        Ok(font)
+    }
+
+    /// Read and parse the `.fnt` file, resolving its pages relative to it.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TangerineError> {
+        let path = path.as_ref();
+        let mut font = Self::parse(&std::fs::read_to_string(path)?)?;
+        if let Some(directory) = path.parent() {
+            for page in &mut font.pages {
+                *page = directory.join(&*page);
+            }
+        }
+        Ok(font)
+    }
+
+    /// Open the images of the pages the font refers to.
+    pub fn open_pages(&self) -> Result<Vec<DynamicImage>, TangerineError> {
+        self.pages
+            .iter()
+            .map(|page| Ok(image::open(page)?))
+            .collect()
+    }
+}
+
+/// Split a line of a `.fnt` file into its tag and its `key=value` pairs.
+fn parse_fnt_line(line: &str) -> (&str, HashMap<&str, &str>) {
+    let line = line.trim();
+    let (tag, mut rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+    let mut attributes = HashMap::new();
+    while let Some((key, after)) = rest.trim_start().split_once('=') {
+        let (value, after) = match after.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => after.split_once(char::is_whitespace).unwrap_or((after, "")),
+        };
+        attributes.insert(key.trim(), value);
+        rest = after;
+    }
+
+    (tag, attributes)
+}
+
+/// The most pages a `.fnt` file may have when it doesn't say how many.
+const MAX_FNT_PAGES: usize = 256;
+
+fn invalid_fnt(tag: &str, key: &str) -> TangerineError {
+    TangerineError::InvalidFont(format!("missing or invalid `{key}` of `{tag}`"))
+}
+
+fn fnt_number<T: FromStr>(
+    attributes: &HashMap<&str, &str>,
+    tag: &str,
+    key: &str,
+) -> Result<T, TangerineError> {
+    attributes
+        .get(key)
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| invalid_fnt(tag, key))
+}
+
+fn fnt_char(
+    attributes: &HashMap<&str, &str>,
+    tag: &str,
+    key: &str,
+) -> Result<char, TangerineError> {
+    char::from_u32(fnt_number(attributes, tag, key)?).ok_or_else(|| invalid_fnt(tag, key))
+}
+
+impl<'me, const N: usize> AtlasBuilder<'me, N> {
+    /// Options glyphs are loaded with, a line of text being one unit tall.
+    pub(crate) fn glyph_options(&self, line_height: f32) -> SpriteLoadOptions {
+        SpriteLoadOptions {
+            pivot: [0., 0.],
+            pixels_per_unit: Some(line_height),
+            ..self.default_options
+        }
+    }
+
+    /// Add the glyphs of the BMFont from the images of its pages. Fails if a
+    /// glyph is on a page that wasn't passed or reaches outside of its page.
+    pub fn add_bmfont(
+        mut self,
+        pages: impl IntoIterator<Item = impl Into<DynamicImage>>,
+        font: &BmFont,
+    ) -> Result<(Self, BitmapFont), TangerineError> {
+        let options = self.glyph_options(font.line_height);
+        let pages = pages
+            .into_iter()
+            .map(|page| crate::atlas::load_image(page.into(), &options))
+            .collect::<Vec<_>>();
+
+        // Checked up front to not queue a part of the font
+        for bm_char in &font.chars {
+            let Some(page) = pages.get(bm_char.page) else {
+                return Err(TangerineError::InvalidFont(format!(
+                    "glyph {:?} is on page {} but there are only {} pages",
+                    bm_char.character,
+                    bm_char.page,
+                    pages.len()
+                )));
+            };
+
+            let outside = |position: u32, size: u32, page_size: u32| {
+                position.checked_add(size).is_none_or(|end| end > page_size)
+            };
+            let rect = bm_char.rect;
+            if outside(rect.x, rect.width, page.width())
+                || outside(rect.y, rect.height, page.height())
+            {
+                return Err(TangerineError::InvalidFont(format!(
+                    "glyph {:?} is outside of its page",
+                    bm_char.character
+                )));
+            }
+        }
+
+        let mut glyphs = HashMap::new();
+        for bm_char in &font.chars {
+            let SpriteRect {
+                x,
+                y,
+                width,
+                height,
+            } = bm_char.rect;
+
+            let sprite = match width > 0 && height > 0 {
+                true => {
+                    let page = &pages[bm_char.page];
+                    let image = imageops::crop_imm(page, x, y, width, height).to_image();
+                    Some(self.push_sprite(image, options)?)
+                }
+                false => None,
+            };
+
+            glyphs.insert(
+                bm_char.character,
+                Glyph {
+                    sprite,
+                    offset: bm_char.offset,
+                    advance: bm_char.advance,
+                },
+            );
+        }
+
+        let font = BitmapFont {
+            glyphs,
+            kerning: font
+                .kernings
+                .iter()
+                .map(|(left, right, amount)| ((*left, *right), *amount))
+                .collect(),
+            line_height: font.line_height,
+        };
+
+        Ok((self, font))
+    }
+
+    /// Add a font whose glyphs are cells of the same size in a grid, the
+    /// characters are assigned to the cells left to right, top to bottom.
+    /// Fails if the cells are empty or there are more characters than cells.
+    pub fn add_monospace_font(
+        mut self,
+        image: impl Into<DynamicImage>,
+        cell_width: u32,
+        cell_height: u32,
+        chars: impl IntoIterator<Item = char>,
+    ) -> Result<(Self, BitmapFont), TangerineError> {
+        if cell_width == 0 || cell_height == 0 {
+            return Err(TangerineError::InvalidFont(format!(
+                "the cells of the font are {cell_width}x{cell_height} pixels"
+            )));
+        }
+
+        let options = self.glyph_options(cell_height as f32);
+        let image = crate::atlas::load_image(image.into(), &options);
+
+        let cells =
+            SpriteSheet::grid(cell_width, cell_height).slices(image.width(), image.height());
+        let chars = chars.into_iter().collect::<Vec<_>>();
+        if chars.len() > cells.len() {
+            return Err(TangerineError::InvalidFont(format!(
+                "{} characters don't fit into the {} cells of the font",
+                chars.len(),
+                cells.len()
+            )));
+        }
+
+        let mut glyphs = HashMap::new();
+        for (character, cell) in chars.into_iter().zip(cells) {
+            let image = imageops::crop_imm(&image, cell.x, cell.y, cell.width, cell.height);
+            let sprite = self.push_sprite(image.to_image(), options)?;
+            glyphs.insert(
+                character,
+                Glyph {
+                    sprite: Some(sprite),
+                    offset: [0., 0.],
+                    advance: cell_width as f32,
+                },
+            );
+        }
+
+        let font = BitmapFont {
+            glyphs,
+            kerning: HashMap::new(),
+            line_height: cell_height as f32,
+        };
+
+        Ok((self, font))
+    }
+}
+
+/// Horizontal alignment of the lines of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextAlign {
+    /// Lines start at the position.
+    #[default]
+    Left,
+    /// Lines are centered on the position.
+    Center,
+    /// Lines end at the position.
+    Right,
+}
+
+/// How text is broken into lines and aligned, measured in the font's pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextLayout {
+    pub align: TextAlign,
+    /// Words that would make a line wider are moved onto the next line
+    pub max_width: Option<f32>,
+    /// Multiplier of the font's line height
+    pub line_spacing: f32,
+}
+
+impl Default for TextLayout {
+    fn default() -> Self {
+        Self {
+            align: Default::default(),
+            max_width: None,
+            line_spacing: 1.,
+        }
+    }
+}
+
+/// A glyph placed by the layout, relative to the top left of the text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionedGlyph {
+    pub character: char,
+    pub sprite: SpriteIndex,
+    /// Position of the sprite's top left corner in pixels, growing downwards
+    pub position: [f32; 2],
+}
+
+impl TextLayout {
+    /// Place the glyphs of the text, skipping the characters missing from the font.
+    pub fn layout(&self, font: &impl Font, text: &str) -> Vec<PositionedGlyph> {
+        let mut glyphs = vec![];
+
+        for (line_idx, line) in self.break_lines(font, text).iter().enumerate() {
+            let width = advance_of(font, line);
+            let mut pen = match self.align {
+                TextAlign::Left => 0.,
+                TextAlign::Center => -width / 2.,
+                TextAlign::Right => -width,
+            };
+            let top = line_idx as f32 * font.line_height() * self.line_spacing;
+
+            let mut previous = None;
+            for character in line.iter().copied() {
+                let Some(glyph) = font.glyph(character) else {
+                    continue;
+                };
+                if let Some(previous) = previous {
+                    pen += font.kerning(previous, character);
+                }
+
+                if let Some(sprite) = glyph.sprite {
+                    glyphs.push(PositionedGlyph {
+                        character,
+                        sprite,
+                        position: [pen + glyph.offset[0], top + glyph.offset[1]],
+                    });
+                }
+                pen += glyph.advance;
+                previous = Some(character);
+            }
+        }
+
+        glyphs
+    }
+
+    /// Split the text on line breaks and wrap the words at the maximum width.
+    fn break_lines(&self, font: &impl Font, text: &str) -> Vec<Vec<char>> {
+        let mut lines = vec![];
+
+        for paragraph in text.split('\n') {
+            let Some(max_width) = self.max_width else {
+                lines.push(paragraph.chars().collect());
+                continue;
+            };
+
+            let mut line: Vec<char> = vec![];
+            for word in paragraph.split(' ') {
+                let mut candidate = line.clone();
+                if !candidate.is_empty() {
+                    candidate.push(' ');
+                }
+                candidate.extend(word.chars());
+
+                if line.is_empty() || advance_of(font, &candidate) <= max_width {
+                    line = candidate;
+                } else {
+                    lines.push(std::mem::take(&mut line));
+                    line.extend(word.chars());
+                }
+
+                // Words wider than a whole line are broken between characters
+                while line.len() > 1 && advance_of(font, &line) > max_width {
+                    let fitting = (1..line.len())
+                        .rev()
+                        .find(|len| advance_of(font, &line[..*len]) <= max_width)
+                        .unwrap_or(1);
+                    let rest = line.split_off(fitting);
+                    lines.push(std::mem::replace(&mut line, rest));
+                }
+            }
+            lines.push(line);
+        }
+
+        lines
+    }
+}
+
+/// The distance the pen travels over the characters.
+fn advance_of(font: &impl Font, chars: &[char]) -> f32 {
+    let mut advance = 0.;
+    let mut previous = None;
+    for character in chars.iter().copied() {
+        let Some(glyph) = font.glyph(character) else {
+            continue;
+        };
+        if let Some(previous) = previous {
+            advance += font.kerning(previous, character);
+        }
+        advance += glyph.advance;
+        previous = Some(character);
+    }
+    advance
+}
+
+impl<A: Allocator> FrameBuilder<A> {
+    pub fn draw_text<'me, F: Font>(
+        &'me mut self,
+        font: &'me F,
+        text: &'me str,
+    ) -> DrawText<'me, F, A> {
+        DrawText {
+            builder: self,
+            font,
+            text,
+            layer: None,
+            position: [0.; 3].into(),
+            size: 1.,
+            color: [0xFFu8; 3].into(),
+            opacity: 1.,
+            max_width: None,
+            layout: Default::default(),
+        }
+    }
+}
+
+#[must_use]
+pub struct DrawText<'builder, F: Font, A: Allocator> {
+    builder: &'builder mut FrameBuilder<A>,
+    font: &'builder F,
+    text: &'builder str,
+    layer: Option<LayerIdentifier>,
+    position: Vector3<f32>,
+    size: f32,
+    color: EncodedSrgb<u8>,
+    opacity: f32,
+    /// Maximum width of the lines in world units
+    max_width: Option<f32>,
+    layout: TextLayout,
+}
+
+impl<'builder, F: Font, A: Allocator> DrawText<'builder, F, A> {
+    pub fn layer(self, layer: impl Into<LayerIdentifier>) -> Self {
+        Self {
+            layer: Some(layer.into()),
+            ..self
+        }
+    }
+
+    /// The world position of the top of the first line, where it starts,
+    /// is centered or ends depending on the alignment.
+    pub fn pos(mut self, pos: impl Into<Vector3<f32>>) -> Self {
+        self.position = pos.into();
+        self
+    }
+
+    /// Height of a line in world units.
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn color(mut self, color: impl Into<EncodedSrgb<u8>>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    pub fn opacity(mut self, opacity: impl Into<f32>) -> Self {
+        self.opacity = opacity.into();
+        self
+    }
+
+    pub fn align(mut self, align: TextAlign) -> Self {
+        self.layout.align = align;
+        self
+    }
+
+    /// Wrap the lines wider than this many world units.
+    pub fn max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn line_spacing(mut self, line_spacing: f32) -> Self {
+        self.layout.line_spacing = line_spacing;
+        self
+    }
+
+    pub fn done(self) -> &'builder mut FrameBuilder<A> {
+        let units_per_pixel = self.size / self.font.line_height();
+        let layout = TextLayout {
+            max_width: self.max_width.map(|max_width| max_width / units_per_pixel),
+            ..self.layout
+        };
+
+        for glyph in layout.layout(self.font, self.text) {
+            let [x, y] = glyph.position;
+            self.builder.submit_sprite(
+                glyph.sprite,
+                self.layer.clone(),
+                SpriteInstance {
+                    position: [
+                        self.position.x + x * units_per_pixel,
+                        self.position.y - y * units_per_pixel,
+                        self.position.z,
+                    ]
+                    .into(),
+                    transform: SpriteTransform::size(self.size),
+                    color: self.color,
+                    opacity: self.opacity,
+                    ..Default::default()
+                },
+            );
+        }
+
+        self.builder
+    }
+}
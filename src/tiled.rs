@@ -0,0 +1,981 @@
+use std::path::{Path, PathBuf};
+
+use hashbrown::HashMap;
+use image::DynamicImage;
+use mint::Vector2;
+use quick_xml::{events::Event, Reader};
+use serde::Deserialize;
+
+use crate::{
+    AtlasBuilder, Renderer, SpriteIndex, SpriteLoadOptions, SpriteSheet, TangerineError, Tilemap,
+};
+
+/// Bits of a global tile id which flip or rotate the tile, tilemaps ignore them.
+const FLIP_FLAGS: u32 = 0xF000_0000;
+
+/// A custom property of a map, a layer or an object.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TiledProperty {
+    Bool(bool),
+    /// Integers and references to objects
+    Int(i64),
+    Float(f64),
+    /// Strings, colours, files and everything else
+    String(String),
+}
+
+pub type TiledProperties = HashMap<String, TiledProperty>;
+
+/// A map made in the Tiled editor, read from a `.tmx` or a `.tmj` file.
+/// Positions and sizes are in pixels with the Y axis going downwards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledMap {
+    pub columns: u32,
+    pub rows: u32,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub tilesets: Vec<TiledTileset>,
+    /// The layers from the bottom to the top, the layers of groups are
+    /// listed in place of the group
+    pub layers: Vec<TiledLayer>,
+    pub properties: TiledProperties,
+}
+
+/// A tileset sliced out of a single image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledTileset {
+    /// Global id of the first tile of the tileset
+    pub first_gid: u32,
+    pub name: String,
+    /// Path to the image, relative to the map file when loaded with `.load(..)`
+    pub image: PathBuf,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    /// Pixels between the edges of the image and the tiles
+    pub margin: u32,
+    /// Pixels between the neighbouring tiles
+    pub spacing: u32,
+    pub tile_count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledLayer {
+    /// Used as the name of the layer the tilemap is drawn on
+    pub name: String,
+    /// Offset in pixels, including the offsets of the groups the layer is in
+    pub offset: [f32; 2],
+    /// Whether the layer and all the groups it is in are visible
+    pub visible: bool,
+    pub properties: TiledProperties,
+    pub contents: TiledLayerContents,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TiledLayerContents {
+    /// The global ids of the tiles row by row, 0 for empty tiles
+    Tiles {
+        columns: u32,
+        rows: u32,
+        gids: Vec<u32>,
+    },
+    Objects(Vec<TiledObject>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledObject {
+    pub id: u32,
+    pub name: String,
+    /// The class of the object, called its type in older versions of Tiled
+    pub class: String,
+    /// Position of the top left corner, the bottom left one for tile objects
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    /// Clockwise rotation in degrees
+    pub rotation: f32,
+    pub shape: TiledShape,
+    /// The tile of tile objects
+    pub gid: Option<u32>,
+    pub properties: TiledProperties,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TiledShape {
+    Rect,
+    Ellipse,
+    Point,
+    /// Points of the polygon relative to the object's position
+    Polygon(Vec<[f32; 2]>),
+    /// Points of the polyline relative to the object's position
+    Polyline(Vec<[f32; 2]>),
+}
+
+/// Sprites added for the tilesets of a map.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TiledSprites {
+    /// The first global id of every tileset and the sprites of its tiles
+    pub tilesets: Vec<(u32, Vec<SpriteIndex>)>,
+}
+
+impl TiledSprites {
+    /// The sprite of the global tile id, `None` for empty tiles.
+    pub fn sprite(&self, gid: u32) -> Option<SpriteIndex> {
+        let gid = gid & !FLIP_FLAGS;
+        let (first_gid, sprites) = self
+            .tilesets
+            .iter()
+            .filter(|(first_gid, _)| *first_gid <= gid)
+            .max_by_key(|(first_gid, _)| *first_gid)?;
+        sprites.get((gid - first_gid) as usize).copied()
+    }
+}
+
+impl TiledMap {
+    /// Parse a map saved in the XML format. External tilesets can only be
+    /// read by `.load(..)`.
+    pub fn from_tmx(xml: &str) -> Result<Self, TangerineError> {
+        Self::parse_tmx(xml, None)
+    }
+
+    /// Parse a map saved in the JSON format. External tilesets can only be
+    /// read by `.load(..)`.
+    pub fn from_json(json: &str) -> Result<Self, TangerineError> {
+        Self::parse_json(json, None)
+    }
+
+    /// Read and parse the map along with its external tilesets, the format
+    /// is picked by the extension. The images are resolved relative to the
+    /// files referring to them.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TangerineError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("tmx" | "xml") => Self::parse_tmx(&contents, Some(directory)),
+            _ => Self::parse_json(&contents, Some(directory)),
+        }
+    }
+
+    /// Open the images of the tilesets, in the order of the tilesets.
+    pub fn open_images(&self) -> Result<Vec<DynamicImage>, TangerineError> {
+        self.tilesets
+            .iter()
+            .map(|tileset| Ok(image::open(&tileset.image)?))
+            .collect()
+    }
+
+    /// Convert a position in pixels of the map into the world, the top left
+    /// corner of the map being at the origin.
+    pub fn to_world(position: [f32; 2], pixels_per_unit: f32) -> Vector2<f32> {
+        [
+            position[0] / pixels_per_unit,
+            -position[1] / pixels_per_unit,
+        ]
+        .into()
+    }
+
+    /// Name the renderer's layers after the layers of the map, keeping their
+    /// order and starting at the ordinal.
+    pub fn register_layers(&self, renderer: &mut Renderer, first_ordinal: i32) {
+        for (idx, layer) in self.layers.iter().enumerate() {
+            renderer.set_layer(&layer.name, first_ordinal + idx as i32);
+        }
+    }
+
+    /// A tilemap for every visible tile layer, drawn on the layer of the
+    /// same name. They still have to be built by `.update(..)`.
+    pub fn tilemaps(&self, sprites: &TiledSprites, pixels_per_unit: f32) -> Vec<Tilemap> {
+        self.layers
+            .iter()
+            .filter(|layer| layer.visible)
+            .filter_map(|layer| {
+                let TiledLayerContents::Tiles {
+                    columns,
+                    rows,
+                    gids,
+                } = &layer.contents
+                else {
+                    return None;
+                };
+
+                let tile_size = [
+                    self.tile_width as f32 / pixels_per_unit,
+                    self.tile_height as f32 / pixels_per_unit,
+                ];
+                let offset = Self::to_world(layer.offset, pixels_per_unit);
+                let mut tilemap = Tilemap::new(*columns, *rows, tile_size)
+                    .pos([offset.x, offset.y, 0.])
+                    .layer(layer.name.as_str());
+                tilemap.fill(gids.iter().map(|gid| sprites.sprite(*gid)));
+                Some(tilemap)
+            })
+            .collect()
+    }
+
+    /// The objects of the object layer with the name.
+    pub fn objects(&self, layer_name: &str) -> Option<&[TiledObject]> {
+        self.layers.iter().find_map(|layer| match &layer.contents {
+            TiledLayerContents::Objects(objects) if layer.name == layer_name => {
+                Some(objects.as_slice())
+            }
+            _ => None,
+        })
+    }
+
+    fn parse_tmx(xml: &str, directory: Option<&Path>) -> Result<Self, TangerineError> {
+        let map = XmlElement::parse(xml)?;
+        if map.name != "map" {
+            return Err(invalid(format!("expected a map, found {:?}", map.name)));
+        }
+        if map.attribute("infinite")?.unwrap_or(0) != 0 {
+            return Err(invalid("infinite maps are not supported"));
+        }
+
+        let tilesets = map
+            .children("tileset")
+            .map(|tileset| {
+                let first_gid = tileset.required_attribute("firstgid")?;
+                match tileset.attributes.get("source") {
+                    Some(source) => load_external_tileset(first_gid, source, directory),
+                    None => tmx_tileset(first_gid, tileset, directory),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        let mut layers = vec![];
+        tmx_layers(&map, [0., 0.], true, &mut layers)?;
+
+        Ok(Self {
+            columns: map.required_attribute("width")?,
+            rows: map.required_attribute("height")?,
+            tile_width: map.required_attribute("tilewidth")?,
+            tile_height: map.required_attribute("tileheight")?,
+            tilesets,
+            layers,
+            properties: tmx_properties(&map)?,
+        })
+    }
+
+    fn parse_json(json: &str, directory: Option<&Path>) -> Result<Self, TangerineError> {
+        let map: JsonMap = serde_json::from_str(json).map_err(|err| invalid(format!("{err}")))?;
+        if map.infinite {
+            return Err(invalid("infinite maps are not supported"));
+        }
+
+        let tilesets = map
+            .tilesets
+            .into_iter()
+            .map(|tileset| {
+                if let Some(source) = &tileset.source {
+                    return load_external_tileset(tileset.firstgid, source, directory);
+                }
+                json_tileset(tileset.firstgid, tileset, directory)
+            })
+            .collect::<Result<_, _>>()?;
+
+        let mut layers = vec![];
+        json_layers(map.layers, [0., 0.], true, &mut layers)?;
+
+        Ok(Self {
+            columns: map.width,
+            rows: map.height,
+            tile_width: map.tilewidth,
+            tile_height: map.tileheight,
+            tilesets,
+            layers,
+            properties: json_properties(map.properties),
+        })
+    }
+}
+
+impl<'me, const N: usize> AtlasBuilder<'me, N> {
+    /// Slice the images of the map's tilesets into sprites, the images
+    /// being in the order of the tilesets. Fails if there isn't one image per
+    /// tileset or a tileset has more tiles than fit into its image.
+    pub fn add_tiled_map(
+        self,
+        images: impl IntoIterator<Item = impl Into<DynamicImage>>,
+        map: &TiledMap,
+    ) -> Result<(Self, TiledSprites), TangerineError> {
+        let options = self.default_options;
+        self.add_tiled_map_advanced(images, map, options)
+    }
+
+    /// Add the tilesets of the map with options not activated by default.
+    pub fn add_tiled_map_advanced(
+        mut self,
+        images: impl IntoIterator<Item = impl Into<DynamicImage>>,
+        map: &TiledMap,
+        options: impl Into<SpriteLoadOptions>,
+    ) -> Result<(Self, TiledSprites), TangerineError> {
+        let options = options.into();
+        let images = images.into_iter().map(Into::into).collect::<Vec<_>>();
+        if images.len() != map.tilesets.len() {
+            return Err(invalid(format!(
+                "{} images were passed for the {} tilesets of the map",
+                images.len(),
+                map.tilesets.len()
+            )));
+        }
+
+        let sheets = map
+            .tilesets
+            .iter()
+            .map(|tileset| {
+                SpriteSheet::grid(tileset.tile_width, tileset.tile_height)
+                    .margin(tileset.margin, tileset.margin)
+                    .spacing(tileset.spacing, tileset.spacing)
+                    .count(tileset.tile_count as usize)
+            })
+            .collect::<Vec<_>>();
+
+        // Checked up front to not queue a part of the map
+        for ((image, tileset), sheet) in images.iter().zip(&map.tilesets).zip(&sheets) {
+            let tiles = sheet.slices(image.width(), image.height()).len();
+            if tiles != tileset.tile_count as usize {
+                return Err(invalid(format!(
+                    "the tileset {:?} has {} tiles but only {} fit into its image",
+                    tileset.name, tileset.tile_count, tiles
+                )));
+            }
+        }
+
+        let mut tilesets = vec![];
+        for ((image, tileset), sheet) in images.into_iter().zip(&map.tilesets).zip(&sheets) {
+            let sprites;
+            (self, sprites) = self.add_sprite_sheet_advanced(image, sheet, options)?;
+            tilesets.push((tileset.first_gid, sprites));
+        }
+
+        Ok((self, TiledSprites { tilesets }))
+    }
+}
+
+fn invalid(reason: impl ToString) -> TangerineError {
+    TangerineError::InvalidMap(reason.to_string())
+}
+
+fn load_external_tileset(
+    first_gid: u32,
+    source: &str,
+    directory: Option<&Path>,
+) -> Result<TiledTileset, TangerineError> {
+    let Some(directory) = directory else {
+        return Err(invalid(format!(
+            "the external tileset {source:?} can only be read when loading the map from a file"
+        )));
+    };
+
+    let path = directory.join(source);
+    let contents = std::fs::read_to_string(&path)?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("tsx" | "xml") => {
+            let tileset = XmlElement::parse(&contents)?;
+            tmx_tileset(first_gid, &tileset, Some(directory))
+        }
+        _ => {
+            let tileset: JsonTileset =
+                serde_json::from_str(&contents).map_err(|err| invalid(format!("{err}")))?;
+            json_tileset(first_gid, tileset, Some(directory))
+        }
+    }
+}
+
+fn resolve_image(image: &str, directory: Option<&Path>) -> PathBuf {
+    match directory {
+        Some(directory) => directory.join(image),
+        None => PathBuf::from(image),
+    }
+}
+
+fn property_value(kind: &str, value: &str) -> Result<TiledProperty, TangerineError> {
+    let parse_error = || invalid(format!("invalid {kind} property {value:?}"));
+    Ok(match kind {
+        "bool" => TiledProperty::Bool(value.parse().map_err(|_| parse_error())?),
+        "int" | "object" => TiledProperty::Int(value.parse().map_err(|_| parse_error())?),
+        "float" => TiledProperty::Float(value.parse().map_err(|_| parse_error())?),
+        _ => TiledProperty::String(value.to_owned()),
+    })
+}
+
+/// Decode the tile data, stored as little endian global ids.
+fn decode_tiles(
+    data: &str,
+    encoding: Option<&str>,
+    compression: Option<&str>,
+) -> Result<Vec<u32>, TangerineError> {
+    if let Some(compression) = compression.filter(|compression| !compression.is_empty()) {
+        return Err(invalid(format!(
+            "{compression} compressed layers are not supported"
+        )));
+    }
+
+    match encoding {
+        Some("csv") => data
+            .split(',')
+            .map(str::trim)
+            .filter(|gid| !gid.is_empty())
+            .map(|gid| {
+                gid.parse()
+                    .map_err(|_| invalid(format!("invalid tile {gid:?}")))
+            })
+            .collect(),
+        Some("base64") => Ok(decode_base64(data)?
+            .chunks_exact(4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect()),
+        encoding => Err(invalid(format!(
+            "the {encoding:?} encoding of layers is not supported"
+        ))),
+    }
+}
+
+fn decode_base64(data: &str) -> Result<Vec<u8>, TangerineError> {
+    let mut bytes = vec![];
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for character in data
+        .bytes()
+        .filter(|character| !character.is_ascii_whitespace())
+    {
+        let value = match character {
+            b'A'..=b'Z' => character - b'A',
+            b'a'..=b'z' => character - b'a' + 26,
+            b'0'..=b'9' => character - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ => return Err(invalid("invalid base64 tile data")),
+        };
+
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Whitespace or comma separated coordinates of polygons and polylines.
+fn tmx_points(points: &str) -> Result<Vec<[f32; 2]>, TangerineError> {
+    points
+        .split_whitespace()
+        .map(|point| {
+            let (x, y) = point
+                .split_once(',')
+                .ok_or_else(|| invalid(format!("invalid point {point:?}")))?;
+            let parse = |coordinate: &str| {
+                coordinate
+                    .parse::<f32>()
+                    .map_err(|_| invalid(format!("invalid point {point:?}")))
+            };
+            Ok([parse(x)?, parse(y)?])
+        })
+        .collect()
+}
+
+fn tmx_tileset(
+    first_gid: u32,
+    tileset: &XmlElement,
+    directory: Option<&Path>,
+) -> Result<TiledTileset, TangerineError> {
+    let name = tileset.attributes.get("name").cloned().unwrap_or_default();
+    let Some(image) = tileset.child("image") else {
+        return Err(invalid(format!(
+            "the tileset {name:?} is not made of a single image"
+        )));
+    };
+
+    Ok(TiledTileset {
+        first_gid,
+        image: resolve_image(&image.required_attribute::<String>("source")?, directory),
+        tile_width: tileset.required_attribute("tilewidth")?,
+        tile_height: tileset.required_attribute("tileheight")?,
+        margin: tileset.attribute("margin")?.unwrap_or(0),
+        spacing: tileset.attribute("spacing")?.unwrap_or(0),
+        tile_count: tileset.required_attribute("tilecount")?,
+        name,
+    })
+}
+
+fn tmx_properties(element: &XmlElement) -> Result<TiledProperties, TangerineError> {
+    let Some(properties) = element.child("properties") else {
+        return Ok(Default::default());
+    };
+
+    properties
+        .children("property")
+        .map(|property| {
+            let kind = property
+                .attributes
+                .get("type")
+                .map_or("string", String::as_str);
+            // Strings with several lines are stored as the text of the property
+            let value = property.attributes.get("value").unwrap_or(&property.text);
+            Ok((
+                property.required_attribute("name")?,
+                property_value(kind, value)?,
+            ))
+        })
+        .collect()
+}
+
+/// Add the layers among the children of the element, flattening groups.
+fn tmx_layers(
+    element: &XmlElement,
+    offset: [f32; 2],
+    visible: bool,
+    layers: &mut Vec<TiledLayer>,
+) -> Result<(), TangerineError> {
+    for child in element.children.iter() {
+        let name = child.attributes.get("name").cloned().unwrap_or_default();
+        let offset = [
+            offset[0] + child.attribute("offsetx")?.unwrap_or(0.),
+            offset[1] + child.attribute("offsety")?.unwrap_or(0.),
+        ];
+        let visible = visible && child.attribute("visible")?.unwrap_or(1) != 0;
+
+        let contents = match child.name.as_str() {
+            "layer" => {
+                let Some(data) = child.child("data") else {
+                    return Err(invalid(format!("the layer {name:?} has no data")));
+                };
+                let gids = match data.attributes.get("encoding") {
+                    Some(encoding) => decode_tiles(
+                        &data.text,
+                        Some(encoding.as_str()),
+                        data.attributes.get("compression").map(String::as_str),
+                    )?,
+                    None => data
+                        .children("tile")
+                        .map(|tile| Ok(tile.attribute("gid")?.unwrap_or(0)))
+                        .collect::<Result<_, TangerineError>>()?,
+                };
+
+                TiledLayerContents::Tiles {
+                    columns: child.required_attribute("width")?,
+                    rows: child.required_attribute("height")?,
+                    gids,
+                }
+            }
+            "objectgroup" => TiledLayerContents::Objects(
+                child
+                    .children("object")
+                    .map(tmx_object)
+                    .collect::<Result<_, _>>()?,
+            ),
+            "group" => {
+                tmx_layers(child, offset, visible, layers)?;
+                continue;
+            }
+            _ => continue,
+        };
+
+        layers.push(TiledLayer {
+            name,
+            offset,
+            visible,
+            properties: tmx_properties(child)?,
+            contents,
+        });
+    }
+
+    Ok(())
+}
+
+fn tmx_object(object: &XmlElement) -> Result<TiledObject, TangerineError> {
+    let shape = if object.child("point").is_some() {
+        TiledShape::Point
+    } else if object.child("ellipse").is_some() {
+        TiledShape::Ellipse
+    } else if let Some(polygon) = object.child("polygon") {
+        TiledShape::Polygon(tmx_points(
+            &polygon.required_attribute::<String>("points")?,
+        )?)
+    } else if let Some(polyline) = object.child("polyline") {
+        TiledShape::Polyline(tmx_points(
+            &polyline.required_attribute::<String>("points")?,
+        )?)
+    } else {
+        TiledShape::Rect
+    };
+
+    Ok(TiledObject {
+        id: object.attribute("id")?.unwrap_or(0),
+        name: object.attributes.get("name").cloned().unwrap_or_default(),
+        class: object
+            .attributes
+            .get("class")
+            .or(object.attributes.get("type"))
+            .cloned()
+            .unwrap_or_default(),
+        position: [
+            object.attribute("x")?.unwrap_or(0.),
+            object.attribute("y")?.unwrap_or(0.),
+        ],
+        size: [
+            object.attribute("width")?.unwrap_or(0.),
+            object.attribute("height")?.unwrap_or(0.),
+        ],
+        rotation: object.attribute("rotation")?.unwrap_or(0.),
+        shape,
+        gid: object.attribute("gid")?,
+        properties: tmx_properties(object)?,
+    })
+}
+
+fn json_tileset(
+    first_gid: u32,
+    tileset: JsonTileset,
+    directory: Option<&Path>,
+) -> Result<TiledTileset, TangerineError> {
+    let (Some(image), Some(tile_width), Some(tile_height), Some(tile_count)) = (
+        tileset.image,
+        tileset.tilewidth,
+        tileset.tileheight,
+        tileset.tilecount,
+    ) else {
+        return Err(invalid(format!(
+            "the tileset {:?} is not made of a single image",
+            tileset.name
+        )));
+    };
+
+    Ok(TiledTileset {
+        first_gid,
+        name: tileset.name,
+        image: resolve_image(&image, directory),
+        tile_width,
+        tile_height,
+        margin: tileset.margin,
+        spacing: tileset.spacing,
+        tile_count,
+    })
+}
+
+fn json_properties(properties: Vec<JsonProperty>) -> TiledProperties {
+    properties
+        .into_iter()
+        .map(|property| {
+            let value = match property.value {
+                serde_json::Value::Bool(value) => TiledProperty::Bool(value),
+                serde_json::Value::Number(number) => match number.as_i64() {
+                    Some(value) if property.kind != "float" => TiledProperty::Int(value),
+                    _ => TiledProperty::Float(number.as_f64().unwrap_or_default()),
+                },
+                serde_json::Value::String(value) => TiledProperty::String(value),
+                value => TiledProperty::String(value.to_string()),
+            };
+            (property.name, value)
+        })
+        .collect()
+}
+
+/// Add the layers, flattening groups.
+fn json_layers(
+    children: Vec<JsonLayer>,
+    offset: [f32; 2],
+    visible: bool,
+    layers: &mut Vec<TiledLayer>,
+) -> Result<(), TangerineError> {
+    for layer in children {
+        let offset = [offset[0] + layer.offsetx, offset[1] + layer.offsety];
+        let visible = visible && layer.visible;
+
+        let contents = match layer.kind.as_str() {
+            "tilelayer" => {
+                let (Some(columns), Some(rows)) = (layer.width, layer.height) else {
+                    return Err(invalid(format!("the layer {:?} has no size", layer.name)));
+                };
+                let gids = match layer.data {
+                    Some(JsonData::Gids(gids)) => gids,
+                    Some(JsonData::Encoded(data)) => decode_tiles(
+                        &data,
+                        layer.encoding.as_deref(),
+                        layer.compression.as_deref(),
+                    )?,
+                    None => return Err(invalid(format!("the layer {:?} has no data", layer.name))),
+                };
+
+                TiledLayerContents::Tiles {
+                    columns,
+                    rows,
+                    gids,
+                }
+            }
+            "objectgroup" => TiledLayerContents::Objects(
+                layer
+                    .objects
+                    .into_iter()
+                    .map(|object| TiledObject {
+                        id: object.id,
+                        name: object.name,
+                        class: object.class.or(object.kind).unwrap_or_default(),
+                        position: [object.x, object.y],
+                        size: [object.width, object.height],
+                        rotation: object.rotation,
+                        shape: match (
+                            object.point,
+                            object.ellipse,
+                            object.polygon,
+                            object.polyline,
+                        ) {
+                            (true, ..) => TiledShape::Point,
+                            (_, true, ..) => TiledShape::Ellipse,
+                            (_, _, Some(points), _) => TiledShape::Polygon(
+                                points.into_iter().map(|point| [point.x, point.y]).collect(),
+                            ),
+                            (.., Some(points)) => TiledShape::Polyline(
+                                points.into_iter().map(|point| [point.x, point.y]).collect(),
+                            ),
+                            _ => TiledShape::Rect,
+                        },
+                        gid: object.gid,
+                        properties: json_properties(object.properties),
+                    })
+                    .collect(),
+            ),
+            "group" => {
+                json_layers(layer.layers, offset, visible, layers)?;
+                continue;
+            }
+            _ => continue,
+        };
+
+        layers.push(TiledLayer {
+            name: layer.name,
+            offset,
+            visible,
+            properties: json_properties(layer.properties),
+            contents,
+        });
+    }
+
+    Ok(())
+}
+
+/// An element of an XML document along with everything inside of it.
+#[derive(Debug, Default)]
+struct XmlElement {
+    name: String,
+    attributes: HashMap<String, String>,
+    children: Vec<XmlElement>,
+    /// The text and CDATA directly inside of the element
+    text: String,
+}
+
+impl XmlElement {
+    /// Parse the document, returning its root element.
+    fn parse(xml: &str) -> Result<Self, TangerineError> {
+        let xml_error = |err: quick_xml::Error| invalid(format!("{err}"));
+
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+
+        // The elements which are still open, the root is at the bottom
+        let mut open = vec![XmlElement::default()];
+        loop {
+            let event = reader.read_event().map_err(xml_error)?;
+            match &event {
+                Event::Start(start) | Event::Empty(start) => {
+                    let mut element = XmlElement {
+                        name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+                        ..Default::default()
+                    };
+                    for attribute in start.attributes() {
+                        let attribute = attribute.map_err(|err| invalid(format!("{err}")))?;
+                        element.attributes.insert(
+                            String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+                            attribute.unescape_value().map_err(xml_error)?.into_owned(),
+                        );
+                    }
+
+                    if matches!(event, Event::Start(_)) {
+                        open.push(element);
+                    } else if let Some(parent) = open.last_mut() {
+                        parent.children.push(element);
+                    }
+                }
+                Event::End(_) => {
+                    let element = open.pop().filter(|_| !open.is_empty());
+                    match (element, open.last_mut()) {
+                        (Some(element), Some(parent)) => parent.children.push(element),
+                        _ => return Err(invalid("unbalanced XML elements")),
+                    }
+                }
+                Event::Text(text) => {
+                    if let Some(element) = open.last_mut() {
+                        element.text += &text.unescape().map_err(xml_error)?;
+                    }
+                }
+                Event::CData(data) => {
+                    if let Some(element) = open.last_mut() {
+                        element.text += &String::from_utf8_lossy(data);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        match open.pop() {
+            Some(document) if open.is_empty() => document
+                .children
+                .into_iter()
+                .next()
+                .ok_or_else(|| invalid("the document is empty")),
+            _ => Err(invalid("unclosed XML elements")),
+        }
+    }
+
+    fn children<'a, 'n>(
+        &'a self,
+        name: &'n str,
+    ) -> impl Iterator<Item = &'a XmlElement> + use<'a, 'n> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    fn child<'a>(&'a self, name: &str) -> Option<&'a XmlElement> {
+        self.children(name).next()
+    }
+
+    fn attribute<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, TangerineError> {
+        self.attributes
+            .get(name)
+            .map(|value| {
+                value.parse().map_err(|_| {
+                    invalid(format!(
+                        "invalid {name} {value:?} of the {} element",
+                        self.name
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    fn required_attribute<T: std::str::FromStr>(&self, name: &str) -> Result<T, TangerineError> {
+        self.attribute(name)?
+            .ok_or_else(|| invalid(format!("the {} element has no {name}", self.name)))
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonMap {
+    width: u32,
+    height: u32,
+    tilewidth: u32,
+    tileheight: u32,
+    #[serde(default)]
+    infinite: bool,
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+    #[serde(default)]
+    tilesets: Vec<JsonTileset>,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Deserialize)]
+struct JsonTileset {
+    /// Only in the map, not in external tilesets
+    #[serde(default)]
+    firstgid: u32,
+    source: Option<String>,
+    #[serde(default)]
+    name: String,
+    image: Option<String>,
+    tilewidth: Option<u32>,
+    tileheight: Option<u32>,
+    #[serde(default)]
+    margin: u32,
+    #[serde(default)]
+    spacing: u32,
+    tilecount: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct JsonLayer {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    name: String,
+    width: Option<u32>,
+    height: Option<u32>,
+    data: Option<JsonData>,
+    encoding: Option<String>,
+    compression: Option<String>,
+    #[serde(default)]
+    offsetx: f32,
+    #[serde(default)]
+    offsety: f32,
+    #[serde(default = "visible_by_default")]
+    visible: bool,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+    #[serde(default)]
+    objects: Vec<JsonObject>,
+    /// The layers of a group
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+}
+
+fn visible_by_default() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonData {
+    Gids(Vec<u32>),
+    Encoded(String),
+}
+
+#[derive(Deserialize)]
+struct JsonProperty {
+    name: String,
+    #[serde(rename = "type", default)]
+    kind: String,
+    value: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct JsonObject {
+    #[serde(default)]
+    id: u32,
+    #[serde(default)]
+    name: String,
+    class: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    x: f32,
+    #[serde(default)]
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    #[serde(default)]
+    rotation: f32,
+    #[serde(default)]
+    point: bool,
+    #[serde(default)]
+    ellipse: bool,
+    polygon: Option<Vec<JsonPoint>>,
+    polyline: Option<Vec<JsonPoint>>,
+    gid: Option<u32>,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Deserialize)]
+struct JsonPoint {
+    x: f32,
+    y: f32,
+}
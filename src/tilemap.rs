@@ -0,0 +1,283 @@
+use std::{alloc::Allocator, ops::Range, sync::Arc};
+
+use mint::{Vector2, Vector3};
+use wgpu::util::DeviceExt;
+
+use crate::{
+    FrameBuilder, LayerIdentifier, Renderer, SpriteIndex, SpriteInstance, SpriteTransform,
+    TangerineError,
+};
+
+/// Tiles along each side of a chunk.
+pub const TILEMAP_CHUNK_SIZE: u32 = 16;
+
+/// The instances of a chunk's tiles on the GPU, grouped by sprite.
+#[derive(Debug)]
+pub(crate) struct ChunkMesh {
+    pub buffer: wgpu::Buffer,
+    pub batches: Vec<(SpriteIndex, Range<u32>)>,
+    /// World-aligned `[min, max]` corners of the chunk, to cull it per camera
+    pub bounds: [[f32; 2]; 2],
+}
+
+/// A chunk of a tilemap submitted to a frame.
+#[derive(Debug, Clone)]
+pub(crate) struct TilemapRenderData {
+    pub layer: Option<LayerIdentifier>,
+    pub mesh: Arc<ChunkMesh>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Chunk {
+    /// Whether the tiles changed since the mesh was built
+    dirty: bool,
+    /// `None` for chunks without any tiles
+    mesh: Option<Arc<ChunkMesh>>,
+    /// World-aligned `[min, max]` corners of the chunk
+    bounds: [[f32; 2]; 2],
+}
+
+/// A grid of sprites drawn as a whole. The tiles are uploaded to the GPU in
+/// square chunks by `.update(..)`, which only rebuilds the chunks whose tiles
+/// changed since.
+#[derive(Debug, Clone)]
+pub struct Tilemap {
+    columns: u32,
+    rows: u32,
+    tiles: Vec<Option<SpriteIndex>>,
+    tile_size: Vector2<f32>,
+    /// World position of the top left corner of the first tile
+    position: Vector3<f32>,
+    layer: Option<LayerIdentifier>,
+    chunks: Vec<Chunk>,
+}
+
+impl Tilemap {
+    /// An empty tilemap, the rows going downwards from its position.
+    pub fn new(columns: u32, rows: u32, tile_size: impl Into<Vector2<f32>>) -> Self {
+        let chunk_count = columns.div_ceil(TILEMAP_CHUNK_SIZE) as usize
+            * rows.div_ceil(TILEMAP_CHUNK_SIZE) as usize;
+
+        let mut tilemap = Self {
+            columns,
+            rows,
+            tiles: vec![None; columns as usize * rows as usize],
+            tile_size: tile_size.into(),
+            position: [0.; 3].into(),
+            layer: None,
+            chunks: vec![Default::default(); chunk_count],
+        };
+        tilemap.invalidate();
+        tilemap
+    }
+
+    pub fn layer(mut self, layer: impl Into<LayerIdentifier>) -> Self {
+        self.layer = Some(layer.into());
+        self
+    }
+
+    pub fn pos(mut self, pos: impl Into<Vector3<f32>>) -> Self {
+        self.set_position(pos);
+        self
+    }
+
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    pub fn tile_size(&self) -> Vector2<f32> {
+        self.tile_size
+    }
+
+    pub fn position(&self) -> Vector3<f32> {
+        self.position
+    }
+
+    pub fn set_position(&mut self, pos: impl Into<Vector3<f32>>) {
+        self.position = pos.into();
+        self.invalidate();
+    }
+
+    pub fn set_tile_size(&mut self, tile_size: impl Into<Vector2<f32>>) {
+        self.tile_size = tile_size.into();
+        self.invalidate();
+    }
+
+    /// The sprite of the tile, `None` for empty tiles or outside of the map.
+    pub fn tile(&self, column: u32, row: u32) -> Option<SpriteIndex> {
+        if column < self.columns && row < self.rows {
+            self.tiles[self.tile_index(column, row)]
+        } else {
+            None
+        }
+    }
+
+    /// Change the sprite of the tile, tiles outside of the map are ignored.
+    pub fn set_tile(&mut self, column: u32, row: u32, sprite: Option<SpriteIndex>) {
+        if column >= self.columns || row >= self.rows {
+            return;
+        }
+
+        let idx = self.tile_index(column, row);
+        let tile = &mut self.tiles[idx];
+        if *tile != sprite {
+            *tile = sprite;
+            let chunk = self.chunk_index(column / TILEMAP_CHUNK_SIZE, row / TILEMAP_CHUNK_SIZE);
+            self.chunks[chunk].dirty = true;
+        }
+    }
+
+    /// Set every tile of the map, row by row.
+    pub fn fill(&mut self, tiles: impl IntoIterator<Item = Option<SpriteIndex>>) {
+        for (idx, sprite) in tiles.into_iter().take(self.tiles.len()).enumerate() {
+            let columns = self.columns as usize;
+            self.set_tile((idx % columns) as u32, (idx / columns) as u32, sprite);
+        }
+    }
+
+    /// The `[column, row]` of the tile at the world position.
+    pub fn tile_at(&self, world_position: impl Into<Vector2<f32>>) -> Option<[u32; 2]> {
+        let world_position = world_position.into();
+        let column = ((world_position.x - self.position.x) / self.tile_size.x).floor();
+        let row = ((self.position.y - world_position.y) / self.tile_size.y).floor();
+
+        (column >= 0. && row >= 0. && column < self.columns as f32 && row < self.rows as f32)
+            .then_some([column as u32, row as u32])
+    }
+
+    /// Rebuild the chunks whose tiles changed. Tiles are stretched to fill
+    /// their cell using the size of their sprite at the time.
+    pub fn update(&mut self, renderer: &Renderer) -> Result<(), TangerineError> {
+        let chunk_columns = self.columns.div_ceil(TILEMAP_CHUNK_SIZE);
+        for chunk_idx in 0..self.chunks.len() {
+            if !self.chunks[chunk_idx].dirty {
+                continue;
+            }
+
+            let chunk_column = chunk_idx as u32 % chunk_columns;
+            let chunk_row = chunk_idx as u32 / chunk_columns;
+            let columns = chunk_column * TILEMAP_CHUNK_SIZE
+                ..((chunk_column + 1) * TILEMAP_CHUNK_SIZE).min(self.columns);
+            let rows = chunk_row * TILEMAP_CHUNK_SIZE
+                ..((chunk_row + 1) * TILEMAP_CHUNK_SIZE).min(self.rows);
+
+            let mut tiles = rows
+                .clone()
+                .flat_map(|row| columns.clone().map(move |column| (column, row)))
+                .filter_map(|(column, row)| Some((self.tile(column, row)?, column, row)))
+                .collect::<Vec<_>>();
+            // The chunk stays dirty, the ones before were valid and are rebuilt
+            if let Some((unknown, ..)) = tiles
+                .iter()
+                .find(|(sprite, ..)| *sprite >= renderer.sprites.len())
+            {
+                return Err(TangerineError::UnknownSprite(*unknown));
+            }
+            // Tiles on the same page are drawn together to not switch textures
+            tiles.sort_by_key(|(sprite, ..)| (renderer.sprites[*sprite].page, *sprite));
+
+            let mut instances = Vec::with_capacity(tiles.len());
+            let mut batches: Vec<(SpriteIndex, Range<u32>)> = vec![];
+            for (sprite, column, row) in tiles {
+                match batches.last_mut() {
+                    Some((batch_sprite, batch_instances)) if *batch_sprite == sprite => {
+                        batch_instances.end += 1;
+                    }
+                    _ => {
+                        let first = instances.len() as u32;
+                        batches.push((sprite, first..first + 1));
+                    }
+                }
+
+                let [quad_width, quad_height] = renderer.sprites[sprite].quad_size;
+                let instance = SpriteInstance {
+                    position: [
+                        self.position.x + column as f32 * self.tile_size.x,
+                        self.position.y - row as f32 * self.tile_size.y,
+                        self.position.z,
+                    ]
+                    .into(),
+                    transform: SpriteTransform::scaled([
+                        self.tile_size.x / quad_width,
+                        self.tile_size.y / quad_height,
+                    ]),
+                    pivot: Some([0., 0.]),
+                    ..Default::default()
+                };
+                instances.push(instance.raw());
+            }
+
+            let chunk = &mut self.chunks[chunk_idx];
+            let bounds = chunk.bounds;
+            chunk.dirty = false;
+            chunk.mesh = (!instances.is_empty()).then(|| {
+                let buffer =
+                    renderer
+                        .device()
+                        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                            label: None,
+                            contents: bytemuck::cast_slice(&instances),
+                            usage: wgpu::BufferUsages::VERTEX,
+                        });
+                Arc::new(ChunkMesh {
+                    buffer,
+                    batches,
+                    bounds,
+                })
+            });
+        }
+
+        Ok(())
+    }
+
+    fn tile_index(&self, column: u32, row: u32) -> usize {
+        row as usize * self.columns as usize + column as usize
+    }
+
+    fn chunk_index(&self, chunk_column: u32, chunk_row: u32) -> usize {
+        let chunk_columns = self.columns.div_ceil(TILEMAP_CHUNK_SIZE) as usize;
+        chunk_row as usize * chunk_columns + chunk_column as usize
+    }
+
+    /// Mark every chunk to be rebuilt and recalculate their bounds.
+    fn invalidate(&mut self) {
+        let chunk_columns = self.columns.div_ceil(TILEMAP_CHUNK_SIZE);
+        let chunk_extent = [
+            TILEMAP_CHUNK_SIZE as f32 * self.tile_size.x,
+            TILEMAP_CHUNK_SIZE as f32 * self.tile_size.y,
+        ];
+
+        for (idx, chunk) in self.chunks.iter_mut().enumerate() {
+            let chunk_column = idx as u32 % chunk_columns;
+            let chunk_row = idx as u32 / chunk_columns;
+            let left = self.position.x + chunk_column as f32 * chunk_extent[0];
+            let top = self.position.y - chunk_row as f32 * chunk_extent[1];
+
+            chunk.dirty = true;
+            chunk.bounds = [[left, top - chunk_extent[1]], [left + chunk_extent[0], top]];
+        }
+    }
+}
+
+impl<A: Allocator> FrameBuilder<A> {
+    /// Draw the chunks of the tilemap which were built by `.update(..)`. Every
+    /// camera only draws the chunks it sees.
+    pub fn draw_tilemap(&mut self, tilemap: &Tilemap) -> &mut Self {
+        for mesh in tilemap
+            .chunks
+            .iter()
+            .filter_map(|chunk| chunk.mesh.as_ref())
+        {
+            self.submit_tilemap_chunk(TilemapRenderData {
+                layer: tilemap.layer.clone(),
+                mesh: mesh.clone(),
+            });
+        }
+
+        self
+    }
+}
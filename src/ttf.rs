@@ -0,0 +1,111 @@
+use std::path::Path;
+
+use ab_glyph::{Font as _, FontArc, PxScale, ScaleFont as _};
+use hashbrown::HashMap;
+use image::{Rgba, RgbaImage};
+
+use crate::{Font, Glyph, Renderer, TangerineError};
+
+/// A TrueType or OpenType font rasterized at a fixed pixel size. Glyphs are
+/// added to the atlas on demand by `.cache_glyphs(..)`.
+#[derive(Debug, Clone)]
+pub struct TrueTypeFont {
+    font: FontArc,
+    scale: PxScale,
+    glyphs: HashMap<char, Glyph>,
+}
+
+impl TrueTypeFont {
+    /// Parse the font to be rasterized with lines this many pixels tall.
+    pub fn from_bytes(bytes: Vec<u8>, pixel_size: f32) -> Result<Self, TangerineError> {
+        let font = FontArc::try_from_vec(bytes)
+            .map_err(|err| TangerineError::InvalidFont(err.to_string()))?;
+
+        Ok(Self {
+            font,
+            scale: PxScale::from(pixel_size),
+            glyphs: HashMap::new(),
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>, pixel_size: f32) -> Result<Self, TangerineError> {
+        Self::from_bytes(std::fs::read(path)?, pixel_size)
+    }
+
+    /// Rasterize the characters of the text which aren't in the atlas yet
+    /// and add them to it, the sprites of the cached glyphs are kept. Fails
+    /// without caching any of the characters if a glyph doesn't fit into
+    /// the atlas.
+    pub fn cache_glyphs(
+        &mut self,
+        renderer: &mut Renderer,
+        text: &str,
+    ) -> Result<(), TangerineError> {
+        let scaled = self.font.as_scaled(self.scale);
+        let mut missing = text
+            .chars()
+            .filter(|character| !self.glyphs.contains_key(character) && *character != '\n')
+            .collect::<Vec<_>>();
+        missing.sort_unstable();
+        missing.dedup();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let mut atlas = renderer.atlas();
+        let options = atlas.glyph_options(self.line_height());
+
+        let mut glyphs = vec![];
+        for character in missing {
+            let glyph_id = self.font.glyph_id(character);
+            let glyph = glyph_id.with_scale_and_position(self.scale, (0., scaled.ascent()));
+
+            let mut sprite = None;
+            let mut offset = [0.; 2];
+            if let Some(outlined) = self.font.outline_glyph(glyph) {
+                let bounds = outlined.px_bounds();
+                let mut image = RgbaImage::new(bounds.width() as u32, bounds.height() as u32);
+                outlined.draw(|x, y, coverage| {
+                    if x < image.width() && y < image.height() {
+                        image.put_pixel(x, y, Rgba([0xFF, 0xFF, 0xFF, (coverage * 255.) as u8]));
+                    }
+                });
+
+                if image.width() > 0 && image.height() > 0 {
+                    sprite = Some(atlas.push_sprite(image, options)?);
+                    offset = [bounds.min.x, bounds.min.y];
+                }
+            }
+
+            glyphs.push((
+                character,
+                Glyph {
+                    sprite,
+                    offset,
+                    advance: scaled.h_advance(glyph_id),
+                },
+            ));
+        }
+
+        // Only known once the atlas took the sprites
+        atlas.try_finalize()?;
+        self.glyphs.extend(glyphs);
+        Ok(())
+    }
+}
+
+impl Font for TrueTypeFont {
+    fn glyph(&self, character: char) -> Option<&Glyph> {
+        self.glyphs.get(&character)
+    }
+
+    fn kerning(&self, left: char, right: char) -> f32 {
+        let scaled = self.font.as_scaled(self.scale);
+        scaled.kern(self.font.glyph_id(left), self.font.glyph_id(right))
+    }
+
+    fn line_height(&self) -> f32 {
+        let scaled = self.font.as_scaled(self.scale);
+        scaled.height() + scaled.line_gap()
+    }
+}
@@ -5,6 +5,12 @@ use bytemuck::{Pod, Zeroable};
 pub(crate) struct Vertex {
     pub position: [f32; 3],
     pub tex_coords: [f32; 2],
+    /// Which corner of the quad the vertex is, `[0, 0]` being the top left
+    pub corner: [f32; 2],
+    /// Size of the whole quad in world units
+    pub quad_size: [f32; 2],
+    /// Size of the whole sprite in the texture coordinates
+    pub tex_size: [f32; 2],
 }
 
 impl Vertex {
@@ -24,6 +30,52 @@ impl Vertex {
                     shader_location: 1,
                     format: wgpu::VertexFormat::Float32x2,
                 },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 5]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 7]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 9]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+            ],
+        }
+    }
+}
+
+/// A vertex of the untextured shapes, already in world space.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable, PartialEq)]
+pub(crate) struct ShapeVertex {
+    pub position: [f32; 3],
+    /// sRGB colour with every component divided by 255, like the sprite tints
+    pub color: [f32; 4],
+}
+
+impl ShapeVertex {
+    pub fn layout() -> wgpu::VertexBufferLayout<'static> {
+        use std::mem;
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<ShapeVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
             ],
         }
     }
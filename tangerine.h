@@ -12,6 +12,28 @@ extern "C" {
 
 typedef struct tangerine_renderer tangerine_renderer;
 
+typedef struct tangerine_renderer_builder tangerine_renderer_builder;
+
+typedef enum {
+  TANGERINE_VSYNC_ON = 0,
+  TANGERINE_VSYNC_OFF = 1,
+  TANGERINE_VSYNC_MAILBOX = 2,
+} tangerine_vsync;
+
+/* Bits of the backends passed to tangerine_builder_set_backends */
+#define TANGERINE_BACKEND_VULKAN (1u << 1)
+#define TANGERINE_BACKEND_GL (1u << 5)
+#define TANGERINE_BACKEND_METAL (1u << 2)
+#define TANGERINE_BACKEND_DX12 (1u << 3)
+#define TANGERINE_BACKEND_DX11 (1u << 4)
+#define TANGERINE_BACKEND_BROWSER_WEBGPU (1u << 6)
+
+typedef enum {
+  TANGERINE_LIMITS_DEFAULT = 0,
+  TANGERINE_LIMITS_DOWNLEVEL = 1,
+  TANGERINE_LIMITS_WEBGL2 = 2,
+} tangerine_limits;
+
 typedef struct {
   unsigned int cursor_position_x;
   unsigned int cursor_position_y;
@@ -21,6 +43,44 @@ typedef void (*tangerine_draw_fn)(tangerine_renderer *, tangerine_input);
 
 struct tangerine_renderer *tangerine_new(void);
 
+struct tangerine_renderer_builder *tangerine_builder_new(void);
+
+void tangerine_builder_set_backends(struct tangerine_renderer_builder *builder,
+                                    unsigned int backends);
+
+void tangerine_builder_set_high_performance(
+    struct tangerine_renderer_builder *builder, bool high_performance);
+
+/* Returns false and keeps the previous name if the name isn't valid UTF-8 */
+bool tangerine_builder_set_adapter_name(
+    struct tangerine_renderer_builder *builder, const char *name);
+
+void tangerine_builder_set_vsync(struct tangerine_renderer_builder *builder,
+                                 tangerine_vsync vsync);
+
+void tangerine_builder_set_sample_count(
+    struct tangerine_renderer_builder *builder, unsigned int sample_count);
+
+void tangerine_builder_set_clear_color(
+    struct tangerine_renderer_builder *builder, unsigned char *color_rgb);
+
+/* Bits of wgpu's Features the device is required to have */
+void tangerine_builder_set_features(struct tangerine_renderer_builder *builder,
+                                    uint64_t features);
+
+/* A max_texture_dimension_2d of 0 keeps the one of the limits */
+void tangerine_builder_set_limits(struct tangerine_renderer_builder *builder,
+                                  tangerine_limits limits,
+                                  unsigned int max_texture_dimension_2d);
+
+/* 0 makes every sprite one unit tall */
+void tangerine_builder_set_pixels_per_unit(
+    struct tangerine_renderer_builder *builder, float pixels_per_unit);
+
+/* Consumes the builder, returns NULL if the renderer could not be created */
+struct tangerine_renderer *
+tangerine_new_with_builder(struct tangerine_renderer_builder *builder);
+
 void tangerine_delete(struct tangerine_renderer *renderer);
 
 void tangerine_set_title(struct tangerine_renderer *renderer,
@@ -1,7 +1,20 @@
 from typing import Callable, Any, Union
 
 class Renderer:
-    def __init__(self) -> None: ...
+    def __init__(
+        self,
+        *,
+        vsync: str | None = None,
+        sample_count: int | None = None,
+        adapter: str | None = None,
+        high_performance: bool | None = None,
+        backends: list[str] | None = None,
+        features: int | None = None,
+        limits: str | None = None,
+        max_texture_dimension: int | None = None,
+        clear_color: Tuple[int, int, int] | Tuple[float, float, float] | None = None,
+        pixels_per_unit: float | None = None,
+    ) -> None: ...
     def run(self, draw_callback: Callable[[Any, Any], None]) -> None: ...
     def set_title(self, title: str) -> None: ...
     def set_background_color(
@@ -9,7 +22,7 @@ class Renderer:
     ) -> None: ...
     def draw(
         self,
-        sprite_index: int,
+        sprite: int | str,
         position: Tuple[float, float],
         *,
         layer: Union[str, int] = 0,
@@ -18,7 +31,7 @@ class Renderer:
         scale: float | Tuple[float, float] = 1.0,
         opacity: float = 1.0,
     ) -> None: ...
-    def add_sprite(self, buffer: bytes) -> int: ...
+    def add_sprite(self, buffer: bytes, *, name: str | None = None) -> int: ...
 
 class Input:
     def __init__(self):
@@ -0,0 +1,73 @@
+use chrono::Duration;
+use tangerine::{Animation, AnimationMode, AnimationPlayer, TangerineError};
+
+/// Sprites shown by the player after each of the steps of 100ms.
+fn play(mode: AnimationMode, steps: usize) -> Vec<usize> {
+    let animation = Animation::uniform([10, 11, 12], Duration::milliseconds(100)).mode(mode);
+    let mut player = AnimationPlayer::new(animation).unwrap();
+
+    (0..steps)
+        .map(|_| {
+            player.advance(Duration::milliseconds(100));
+            player.sprite()
+        })
+        .collect()
+}
+
+#[test]
+fn looping() {
+    assert_eq!(play(AnimationMode::Loop, 5), [11, 12, 10, 11, 12]);
+}
+
+#[test]
+fn ping_pong() {
+    assert_eq!(play(AnimationMode::PingPong, 6), [11, 12, 11, 10, 11, 12]);
+}
+
+#[test]
+fn once() {
+    assert_eq!(play(AnimationMode::Once, 4), [11, 12, 12, 12]);
+
+    let mut player = AnimationPlayer::new(
+        Animation::uniform([0, 1], Duration::milliseconds(50)).mode(AnimationMode::Once),
+    )
+    .unwrap();
+    player.advance(Duration::milliseconds(500));
+    assert!(player.is_finished());
+    assert_eq!(player.frame(), 1);
+}
+
+#[test]
+fn skips_frames() {
+    let mut player = AnimationPlayer::new(Animation::uniform(
+        [0, 1, 2, 3],
+        Duration::milliseconds(100),
+    ))
+    .unwrap();
+    player.advance(Duration::milliseconds(250));
+    assert_eq!(player.sprite(), 2);
+    player.advance(Duration::milliseconds(60));
+    assert_eq!(player.sprite(), 3);
+}
+
+#[test]
+fn skips_whole_loops() {
+    let mut player =
+        AnimationPlayer::new(Animation::uniform([0, 1, 2], Duration::milliseconds(100))).unwrap();
+    player.advance(Duration::milliseconds(50));
+    // Thousands of years of loops
+    player.advance(Duration::days(3_000_000) + Duration::milliseconds(100));
+    assert_eq!(player.sprite(), 1);
+    player.advance(Duration::milliseconds(40));
+    assert_eq!(player.sprite(), 1);
+    player.advance(Duration::milliseconds(10));
+    assert_eq!(player.sprite(), 2);
+}
+
+#[test]
+fn empty_animation() {
+    assert!(matches!(
+        AnimationPlayer::new(Animation::default()),
+        Err(TangerineError::EmptyAnimation)
+    ));
+}
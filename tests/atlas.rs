@@ -0,0 +1,224 @@
+use std::path::PathBuf;
+
+use image::{Rgba, RgbaImage};
+use tangerine::{FrameBuilder, Renderer, RendererBuilder, SpriteLoadOptions, TangerineError};
+
+/// A renderer whose atlas pages are at most 64 pixels wide and tall.
+fn small_renderer() -> Renderer {
+    RendererBuilder::default()
+        .limits(wgpu::Limits {
+            max_texture_dimension_2d: 64,
+            ..wgpu::Limits::downlevel_defaults()
+        })
+        .build_headless([64, 64])
+        .unwrap()
+}
+
+#[test]
+fn sprite_larger_than_page() {
+    let mut renderer = small_renderer();
+    let [small] = renderer.atlas().add_sprite(RgbaImage::new(8, 8)).finalize();
+
+    let err = renderer
+        .atlas()
+        .add_sprite(RgbaImage::new(8, 8))
+        .add_sprite_advanced(
+            RgbaImage::new(60, 8),
+            SpriteLoadOptions {
+                padding: 8,
+                ..Default::default()
+            },
+        )
+        .try_finalize()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        TangerineError::SpriteTooLarge {
+            sprite: 2,
+            width: 68,
+            height: 16,
+            max_size: 64,
+        }
+    ));
+
+    // Nothing was added by the failed attempt
+    let [next] = renderer
+        .atlas()
+        .add_sprite(RgbaImage::new(64, 64))
+        .try_finalize_and_repack()
+        .unwrap();
+    assert_eq!((small, next), (0, 1));
+}
+
+#[test]
+fn sprites_on_several_pages() {
+    let mut renderer = small_renderer();
+    // Two of them don't fit onto one page of 64x64 pixels
+    let [red, blue] = renderer
+        .atlas()
+        .add_sprite(RgbaImage::from_pixel(40, 40, Rgba([255, 0, 0, 255])))
+        .add_sprite(RgbaImage::from_pixel(40, 40, Rgba([0, 0, 255, 255])))
+        .finalize();
+
+    let mut frame = FrameBuilder::new_global(renderer.viewport());
+    frame.draw_sprite(red).pos([-1., 0., 0.]).done();
+    frame.draw_sprite(blue).pos([1., 0., 0.]).done();
+    renderer.draw_frame(frame.finalize()).unwrap();
+    let pixels = renderer.read_frame().unwrap().unwrap();
+    // The frame is 6 units tall, so the centres are about 10.7 pixels off its centre
+    assert_eq!(pixels.get_pixel(21, 32), &Rgba([255, 0, 0, 255]));
+    assert_eq!(pixels.get_pixel(43, 32), &Rgba([0, 0, 255, 255]));
+}
+
+/// A red sprite packed between blue ones drawn with MSAA, which samples the
+/// partly covered pixels at their centres beyond the edges of the sprite.
+/// Returns the largest amount of blue in the frame.
+fn bleeding(options: SpriteLoadOptions) -> u8 {
+    let mut renderer = RendererBuilder::default()
+        .sample_count(4)
+        .build_headless([64, 64])
+        .unwrap();
+    let blue = || RgbaImage::from_pixel(4, 4, Rgba([0, 0, 255, 255]));
+    let [_, red, _] = renderer
+        .atlas()
+        .add_sprite_advanced(blue(), options)
+        .add_sprite_advanced(RgbaImage::from_pixel(4, 4, Rgba([255, 0, 0, 255])), options)
+        .add_sprite_advanced(blue(), options)
+        .finalize();
+
+    let mut frame = FrameBuilder::new_global(renderer.viewport());
+    frame.draw_sprite(red).scale(5.5).done();
+    renderer.draw_frame(frame.finalize()).unwrap();
+    let pixels = renderer.read_frame().unwrap().unwrap();
+
+    pixels.pixels().map(|pixel| pixel.0[2]).max().unwrap()
+}
+
+#[test]
+fn padding_and_extrusion_prevent_bleeding() {
+    assert!(bleeding(SpriteLoadOptions::default()) > 0);
+    // The edges blend with the transparent padding or the repeated edge pixels
+    assert_eq!(
+        bleeding(SpriteLoadOptions {
+            padding: 1,
+            ..Default::default()
+        }),
+        0
+    );
+    assert_eq!(
+        bleeding(SpriteLoadOptions {
+            extrude: 1,
+            ..Default::default()
+        }),
+        0
+    );
+}
+
+/// An empty directory for the test's files.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tangerine-{name}"));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn save_and_load_atlas() {
+    let dir = scratch_dir("atlas-round-trip");
+
+    // Only one renderer is alive at a time, the GL backend can't share the display
+    let (manifest, red, blue) = {
+        let mut renderer = Renderer::headless([16, 16]);
+        let [red, blue] = renderer
+            .atlas()
+            .add_named_sprite("red", RgbaImage::from_pixel(5, 3, Rgba([255, 0, 0, 255])))
+            .add_sprite_advanced(
+                RgbaImage::from_fn(4, 4, |x, _| Rgba([0, 0, 255, 60 * x as u8])),
+                SpriteLoadOptions {
+                    padding: 1,
+                    extrude: 2,
+                    pivot: [0., 1.],
+                    ..Default::default()
+                },
+            )
+            .name_sprite(1, "blue")
+            .finalize();
+        renderer.save_atlas(dir.join("atlas.json")).unwrap();
+        (renderer.atlas_manifest("atlas"), red, blue)
+    };
+
+    let mut loaded = Renderer::headless([16, 16]);
+    loaded.load_atlas(dir.join("atlas.json")).unwrap();
+    assert_eq!(loaded.atlas_manifest("atlas"), manifest);
+    assert_eq!(loaded.sprite("red"), Some(red));
+    assert_eq!(loaded.sprite("blue"), Some(blue));
+
+    // The pages saved again from the loaded atlas hold the same pixels
+    let resaved_dir = scratch_dir("atlas-round-trip-resaved");
+    loaded.save_atlas(resaved_dir.join("atlas.json")).unwrap();
+    for page in manifest.pages {
+        let expected = image::open(dir.join(&page.image)).unwrap();
+        let actual = image::open(resaved_dir.join(&page.image)).unwrap();
+        assert_eq!(expected, actual);
+    }
+}
+
+#[test]
+fn malformed_manifests() {
+    let dir = scratch_dir("atlas-malformed");
+    RgbaImage::new(8, 8).save(dir.join("page.png")).unwrap();
+
+    let sprite = |index: usize, page: usize, x: u32, extrude: u32| {
+        format!(
+            r#"{{ "index": {index}, "page": {page}, "x": {x}, "y": {x}, "width": 2, "height": 2,
+                "options": {{ "extrude": {extrude} }} }}"#
+        )
+    };
+    let load = |sprites: &[String]| {
+        let manifest = format!(
+            r#"{{ "pages": [{{ "image": "page.png", "width": 8, "height": 8 }}],
+                "sprites": [{}] }}"#,
+            sprites.join(",")
+        );
+        std::fs::write(dir.join("atlas.json"), manifest).unwrap();
+        Renderer::headless([16, 16]).load_atlas(dir.join("atlas.json"))
+    };
+
+    assert!(load(&[sprite(1, 0, 4, 1), sprite(0, 0, 1, 1)]).is_ok());
+
+    for sprites in [
+        // Page out of range
+        vec![sprite(0, 1, 0, 0)],
+        // Extruded edges before the page's top left corner
+        vec![sprite(0, 0, 1, 2)],
+        // Past the page's bottom right corner
+        vec![sprite(0, 0, 6, 1)],
+        // A gap in the indices
+        vec![sprite(0, 0, 0, 0), sprite(2, 0, 4, 0)],
+        // A duplicated index
+        vec![sprite(0, 0, 0, 0), sprite(0, 0, 4, 0)],
+    ] {
+        let result = load(&sprites);
+        assert!(
+            matches!(result, Err(TangerineError::AtlasManifest(_))),
+            "{sprites:?} loaded as {result:?}"
+        );
+    }
+
+    std::fs::write(dir.join("atlas.json"), "{ \"pages\": 3 }").unwrap();
+    let result = Renderer::headless([16, 16]).load_atlas(dir.join("atlas.json"));
+    assert!(matches!(result, Err(TangerineError::AtlasManifest(_))));
+
+    let result = Renderer::headless([16, 16]).load_atlas(dir.join("missing.json"));
+    assert!(matches!(result, Err(TangerineError::Io(_))));
+
+    // A page larger than the textures of the renderer
+    RgbaImage::new(128, 8).save(dir.join("page.png")).unwrap();
+    std::fs::write(
+        dir.join("atlas.json"),
+        r#"{ "pages": [{ "image": "page.png", "width": 128, "height": 8 }], "sprites": [] }"#,
+    )
+    .unwrap();
+    let result = small_renderer().load_atlas(dir.join("atlas.json"));
+    assert!(matches!(result, Err(TangerineError::AtlasManifest(_))));
+}
@@ -0,0 +1,180 @@
+use chrono::Duration;
+use tangerine::{Camera, CameraController, ViewportProperties, ViewportRect};
+use winit::dpi::PhysicalSize;
+
+fn viewport(camera: Camera) -> ViewportProperties {
+    ViewportProperties {
+        camera: Camera {
+            aspect_ratio: 2.,
+            ..camera
+        },
+        window_size: PhysicalSize::new(200, 100),
+        rect: ViewportRect::FULL,
+    }
+}
+
+fn assert_close(actual: mint::Vector2<f32>, expected: [f32; 2]) {
+    assert!(
+        (actual.x - expected[0]).abs() < 1e-4 && (actual.y - expected[1]).abs() < 1e-4,
+        "{actual:?} is not {expected:?}"
+    );
+}
+
+#[test]
+fn window_to_world_follows_the_camera() {
+    let viewport = viewport(Camera {
+        position: [5., -2.].into(),
+        ..Default::default()
+    });
+
+    assert_close(viewport.window_to_world([100, 50]), [5., -2.]);
+    // The top left corner, the window's Y axis going downwards
+    assert_close(viewport.window_to_world([0, 0]), [-1., 1.]);
+}
+
+#[test]
+fn world_to_window_inverts_window_to_world() {
+    let viewport = viewport(Camera {
+        position: [1.5, 0.5].into(),
+        rotation_rad: 0.7,
+        zoom: 1.5,
+        ..Default::default()
+    });
+
+    for pixel in [[0, 0], [200, 100], [37, 81], [150, 20]] {
+        let world = viewport.window_to_world(pixel);
+        assert_close(
+            viewport.world_to_window(world),
+            pixel.map(|coordinate| coordinate as f32),
+        );
+    }
+}
+
+#[test]
+fn rotation() {
+    let viewport = viewport(Camera {
+        rotation_rad: std::f32::consts::FRAC_PI_2,
+        ..Default::default()
+    });
+
+    // The camera's right points up in the world
+    assert_close(viewport.world_to_window([0., 6.]), [200., 50.]);
+}
+
+#[test]
+fn zoom_towards_keeps_the_anchor() {
+    let mut camera = Camera {
+        position: [2., 1.].into(),
+        ..Default::default()
+    };
+    let anchor = viewport(camera).window_to_world([150, 25]);
+
+    camera.zoom_towards(2., anchor);
+    assert_eq!(camera.zoom, 2.);
+    assert_close(
+        viewport(camera).window_to_world([150, 25]),
+        [anchor.x, anchor.y],
+    );
+}
+
+#[test]
+fn shake_wears_off() {
+    let mut camera = Camera::default();
+    camera.add_trauma(0.6);
+    camera.add_trauma(0.6);
+    assert_eq!(camera.shake.trauma, 1.);
+
+    camera.update_shake(Duration::milliseconds(250));
+    let (offset, rotation) = camera.shake.sample();
+    assert!(offset.x.abs() <= camera.shake.max_offset * 0.75f32.powi(2));
+    assert!(rotation.abs() <= camera.shake.max_rotation_rad * 0.75f32.powi(2));
+
+    camera.update_shake(Duration::seconds(1));
+    assert_eq!(camera.shake.trauma, 0.);
+    assert_eq!(camera.snapped_position(), camera.position);
+}
+
+#[test]
+fn viewport_rects() {
+    let rect = ViewportRect::from([0.5, 0., 0.5, 0.25]);
+    assert_eq!(rect.pixels(PhysicalSize::new(200, 100)), [100, 0, 100, 25]);
+    assert_eq!(rect.aspect_ratio(PhysicalSize::new(200, 100)), 4.);
+
+    // Cut off at the edges of the target
+    let rect = ViewportRect::from([0.75, -0.5, 0.5, 1.]);
+    assert_eq!(rect.pixels(PhysicalSize::new(200, 100)), [150, 0, 50, 50]);
+
+    // The right half of the window shows the camera's view
+    let viewport = ViewportProperties {
+        camera: Camera {
+            position: [5., -2.].into(),
+            ..Default::default()
+        },
+        window_size: PhysicalSize::new(200, 100),
+        rect: [0.5, 0., 0.5, 1.].into(),
+    };
+    assert_close(viewport.window_to_world([150, 50]), [5., -2.]);
+    assert_close(viewport.window_to_world([100, 0]), [2., 1.]);
+    assert_close(viewport.world_to_window([8., -5.]), [200., 100.]);
+}
+
+#[test]
+fn controller_dead_zone() {
+    let mut camera = Camera::default();
+    let mut controller = CameraController::new([0., 0.]).dead_zone([2., 2.]);
+
+    controller.follow([0.5, -0.8]);
+    controller.advance(Duration::milliseconds(16), &mut camera);
+    assert_close(camera.position, [0., 0.]);
+
+    // The camera moves just enough to keep the target at the edge
+    controller.follow([3., -0.8]);
+    controller.advance(Duration::milliseconds(16), &mut camera);
+    assert_close(camera.position, [2., 0.]);
+}
+
+#[test]
+fn controller_smoothing() {
+    let mut camera = Camera::default();
+    let mut controller = CameraController::new([0., 0.]).smoothing(Duration::milliseconds(200));
+    controller.follow([10., 0.]);
+
+    let mut last = 0.;
+    for _ in 0..120 {
+        controller.advance(Duration::milliseconds(16), &mut camera);
+        assert!(camera.position.x >= last && camera.position.x <= 10.);
+        last = camera.position.x;
+    }
+    assert_close(camera.position, [10., 0.]);
+}
+
+#[test]
+fn controller_look_ahead() {
+    let mut camera = Camera::default();
+    let mut controller = CameraController::new([0., 0.]).look_ahead(Duration::milliseconds(500));
+
+    // Moving at 10 units per second
+    controller.follow([1., 0.]);
+    controller.advance(Duration::milliseconds(100), &mut camera);
+    assert_close(camera.position, [6., 0.]);
+
+    controller.advance(Duration::milliseconds(100), &mut camera);
+    assert_close(camera.position, [1., 0.]);
+}
+
+#[test]
+fn controller_bounds() {
+    // The camera sees 3 units in every direction
+    let mut camera = Camera::default();
+    let mut controller = CameraController::new([0., 0.]).bounds([-10., -10.], [10., 10.]);
+
+    controller.follow([20., -8.]);
+    controller.advance(Duration::milliseconds(16), &mut camera);
+    assert_close(camera.position, [7., -7.]);
+
+    // A level smaller than the view is centered
+    let mut controller = CameraController::new([0., 0.]).bounds([0., 0.], [4., 20.]);
+    controller.follow([-5., 1.]);
+    controller.advance(Duration::milliseconds(16), &mut camera);
+    assert_close(camera.position, [2., 3.]);
+}
@@ -0,0 +1,562 @@
+//! Golden-image regression tests. Every scene is rendered by a headless
+//! renderer and compared against a reference PNG in `tests/golden/`.
+//!
+//! Run with `TANGERINE_BLESS=1` to (re)generate the references after an
+//! intentional change in the output. When a comparison fails the actual
+//! frame and a diff image are written next to the test binaries.
+
+use std::path::PathBuf;
+
+use image::{Rgba, RgbaImage};
+use tangerine::{
+    Camera, FrameBuilder, LayerMask, NineSliceFill, Renderer, SpriteIndex, SpriteLoadOptions,
+    SpriteSheet, TangerineError, Tilemap, MAIN_CAMERA,
+};
+
+const FRAME_SIZE: (u32, u32) = (64, 64);
+
+/// How much a rendered frame is allowed to diverge from its reference.
+#[derive(Debug, Clone, Copy)]
+struct Tolerance {
+    /// Largest difference of a single channel that still counts as equal.
+    channel: u8,
+    /// Fraction of pixels that may differ by more than `channel`.
+    pixels: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            channel: 2,
+            pixels: 0.001,
+        }
+    }
+}
+
+fn load_asset(name: &str) -> image::DynamicImage {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("examples/assets")
+        .join(name);
+    image::open(path).unwrap()
+}
+
+/// Render the scene with a fresh headless renderer and return the frame.
+fn render(
+    setup: impl FnOnce(&mut Renderer) -> Vec<SpriteIndex>,
+    draw: impl FnOnce(&mut FrameBuilder, &[SpriteIndex]),
+) -> RgbaImage {
+    let mut renderer = Renderer::headless(FRAME_SIZE);
+    let sprites = setup(&mut renderer);
+
+    let mut frame = FrameBuilder::new_global(renderer.viewport());
+    draw(&mut frame, &sprites);
+    renderer.draw_frame(frame.finalize()).unwrap();
+
+    renderer.read_frame().unwrap().unwrap()
+}
+
+fn quickstart_sprites(renderer: &mut Renderer) -> Vec<SpriteIndex> {
+    renderer
+        .atlas()
+        .add_sprite(load_asset("16x16.png"))
+        .add_sprite(load_asset("8x16.png"))
+        .add_sprite(load_asset("8x8.png"))
+        .finalize_and_repack()
+        .to_vec()
+}
+
+/// Compare the frame against `tests/golden/<name>.png`, writing diff images
+/// and panicking if it diverges more than the tolerance allows.
+fn assert_golden(name: &str, actual: &RgbaImage, tolerance: Tolerance) {
+    let reference_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.png"));
+
+    if std::env::var_os("TANGERINE_BLESS").is_some() {
+        std::fs::create_dir_all(reference_path.parent().unwrap()).unwrap();
+        actual.save(&reference_path).unwrap();
+        eprintln!("blessed {}", reference_path.display());
+        return;
+    }
+
+    assert!(
+        reference_path.exists(),
+        "{name}: {} is missing, run with TANGERINE_BLESS=1 to create it",
+        reference_path.display()
+    );
+
+    let expected = image::open(&reference_path).unwrap().into_rgba8();
+    assert_eq!(
+        expected.dimensions(),
+        actual.dimensions(),
+        "{name}: frame size differs from the reference"
+    );
+
+    let mut diff = RgbaImage::new(actual.width(), actual.height());
+    let mut mismatched = 0;
+    for ((expected, actual), diff) in expected.pixels().zip(actual.pixels()).zip(diff.pixels_mut()) {
+        let delta = expected
+            .0
+            .iter()
+            .zip(actual.0.iter())
+            .map(|(e, a)| e.abs_diff(*a))
+            .max()
+            .unwrap();
+
+        if delta > tolerance.channel {
+            mismatched += 1;
+            *diff = Rgba([255, 0, 0, 255]);
+        } else {
+            *diff = Rgba([0, 0, 0, 255]);
+        }
+    }
+
+    let mismatched_fraction = mismatched as f32 / (actual.width() * actual.height()) as f32;
+    if mismatched_fraction > tolerance.pixels {
+        let diff_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("golden-diffs");
+        std::fs::create_dir_all(&diff_dir).unwrap();
+        actual
+            .save(diff_dir.join(format!("{name}.actual.png")))
+            .unwrap();
+        diff.save(diff_dir.join(format!("{name}.diff.png"))).unwrap();
+
+        panic!(
+            "{name}: {mismatched} pixels ({:.2}%) differ from the reference, see {}",
+            mismatched_fraction * 100.,
+            diff_dir.display()
+        );
+    }
+}
+
+#[test]
+fn quickstart() {
+    let frame = render(quickstart_sprites, |frame, sprites| {
+        for (sprite, x) in sprites.iter().zip([-2., 0., 2.]) {
+            frame.draw_sprite(*sprite).pos([x, 0., 0.]).done();
+        }
+    });
+
+    assert_golden("quickstart", &frame, Tolerance::default());
+}
+
+#[test]
+fn named_sprites() {
+    let frame = render(
+        |renderer| {
+            let _ = renderer
+                .atlas()
+                .add_named_sprite("16x16", load_asset("16x16.png"))
+                .add_named_sprite("8x16", load_asset("8x16.png"))
+                .add_named_sprite("8x8", load_asset("8x8.png"))
+                .finalize_and_repack();
+
+            ["16x16", "8x16", "8x8"]
+                .map(|name| renderer.sprite(name).unwrap())
+                .to_vec()
+        },
+        |frame, sprites| {
+            for (sprite, x) in sprites.iter().zip([-2., 0., 2.]) {
+                frame.draw_sprite(*sprite).pos([x, 0., 0.]).done();
+            }
+        },
+    );
+
+    assert_golden("quickstart", &frame, Tolerance::default());
+}
+
+#[test]
+fn unknown_sprite() {
+    let mut renderer = Renderer::headless(FRAME_SIZE);
+    let sprites = quickstart_sprites(&mut renderer);
+    assert_eq!(renderer.sprite("16x16"), None);
+
+    let mut frame = FrameBuilder::new_global(renderer.viewport());
+    frame.draw_sprite(sprites.len()).done();
+    assert!(matches!(
+        renderer.draw_frame(frame.finalize()),
+        Err(TangerineError::UnknownSprite(idx)) if idx == sprites.len()
+    ));
+}
+
+#[test]
+fn sprite_sheet() {
+    // Four 8x8 cells with a margin of 2 and a spacing of 1 pixels
+    let colors = [[255, 0, 0], [0, 255, 0], [0, 0, 255], [255, 255, 0]];
+    let sheet = RgbaImage::from_fn(21, 21, |x, y| {
+        let cell = |coordinate: u32| match coordinate {
+            2..=9 => Some(0),
+            11..=18 => Some(1),
+            _ => None,
+        };
+        match (cell(x), cell(y)) {
+            (Some(column), Some(row)) => {
+                let [r, g, b] = colors[(row * 2 + column) as usize];
+                Rgba([r, g, b, 255])
+            }
+            _ => Rgba([255, 0, 255, 255]),
+        }
+    });
+
+    let frame = render(
+        |renderer| {
+            let (atlas, sprites) = renderer
+                .atlas()
+                .add_sprite_sheet(sheet, &SpriteSheet::grid(8, 8).margin(2, 2).spacing(1, 1))
+                .unwrap();
+            let _ = atlas.finalize();
+            sprites
+        },
+        |frame, sprites| {
+            assert_eq!(sprites.len(), 4);
+            for (sprite, x) in sprites.iter().zip([-3., -1., 1., 3.]) {
+                frame.draw_sprite(*sprite).pos([x, 0., 0.]).done();
+            }
+        },
+    );
+
+    assert_golden("sprite_sheet", &frame, Tolerance::default());
+}
+
+#[test]
+fn rotated_and_stretched() {
+    let frame = render(quickstart_sprites, |frame, sprites| {
+        frame
+            .draw_sprite(sprites[0])
+            .pos([-1.5, 1.5, 0.])
+            .rotate(std::f32::consts::FRAC_PI_4)
+            .done();
+        frame
+            .draw_sprite(sprites[1])
+            .pos([1.5, 1.5, 0.])
+            .stretch([2., 0.5])
+            .done();
+        frame
+            .draw_sprite(sprites[2])
+            .pos([0., -1.5, 0.])
+            .rotate(1.)
+            .scale(1.5)
+            .done();
+    });
+
+    assert_golden("rotated_and_stretched", &frame, Tolerance::default());
+}
+
+#[test]
+fn opacity_and_tint() {
+    let frame = render(quickstart_sprites, |frame, sprites| {
+        frame
+            .draw_sprite(sprites[0])
+            .pos([-1.5, 0., 0.])
+            .opacity(0.5)
+            .done();
+        frame
+            .draw_sprite(sprites[0])
+            .pos([1.5, 0., 0.])
+            .color([255, 64, 0])
+            .done();
+    });
+
+    assert_golden("opacity_and_tint", &frame, Tolerance::default());
+}
+
+#[test]
+fn flipped_and_cropped() {
+    let frame = render(quickstart_sprites, |frame, sprites| {
+        frame
+            .draw_sprite(sprites[1])
+            .pos([-2., 1., 0.])
+            .flip(true, false)
+            .done();
+        frame
+            .draw_sprite(sprites[1])
+            .pos([0., 1., 0.])
+            .flip(false, true)
+            .done();
+        frame
+            .draw_sprite(sprites[0])
+            .pos([2., 1., 0.])
+            .sub_rect([0., 0., 0.5, 1.])
+            .done();
+        frame
+            .draw_sprite(sprites[0])
+            .pos([-1., -1.5, 0.])
+            .sub_rect([0.25, 0.25, 0.5, 0.5])
+            .done();
+        frame
+            .draw_sprite(sprites[0])
+            .pos([1., -1.5, 0.])
+            .flip(true, true)
+            .sub_rect([0., 0.5, 1., 0.5])
+            .done();
+    });
+
+    assert_golden("flipped_and_cropped", &frame, Tolerance::default());
+}
+
+#[test]
+fn pivots() {
+    let frame = render(
+        |renderer| {
+            let bottom_centre = SpriteLoadOptions {
+                pivot: [0.5, 1.],
+                ..Default::default()
+            };
+            renderer
+                .atlas()
+                .add_sprite(load_asset("16x16.png"))
+                .add_sprite_advanced(load_asset("8x16.png"), bottom_centre)
+                .finalize()
+                .to_vec()
+        },
+        |frame, sprites| {
+            frame
+                .draw_sprite(sprites[1])
+                .pos([-1.5, 0., 0.])
+                .rotate(std::f32::consts::FRAC_PI_4)
+                .done();
+            frame
+                .draw_sprite(sprites[0])
+                .pos([1.5, 0., 0.])
+                .pivot([0., 0.])
+                .rotate(std::f32::consts::FRAC_PI_4)
+                .done();
+        },
+    );
+
+    assert_golden("pivots", &frame, Tolerance::default());
+}
+
+#[test]
+fn pixels_per_unit() {
+    let frame = render(
+        |renderer| {
+            renderer.set_pixels_per_unit(Some(8.));
+            renderer.mutate_camera(|camera| {
+                camera.size = Camera::pixel_perfect_size(FRAME_SIZE.1, 8., 2);
+                camera.pixel_snapping = Some(8.);
+                camera.position = [0.03, 0.04].into();
+            });
+            quickstart_sprites(renderer)
+        },
+        |frame, sprites| {
+            for (sprite, x) in sprites.iter().zip([-1.5, 0., 1.5]) {
+                frame.draw_sprite(*sprite).pos([x, 0., 0.]).done();
+            }
+        },
+    );
+
+    assert_golden("pixels_per_unit", &frame, Tolerance::default());
+}
+
+#[test]
+fn layer_ordering() {
+    let frame = render(
+        |renderer| {
+            renderer.set_layer("background", -1);
+            renderer.set_layer("foreground", 1);
+            quickstart_sprites(renderer)
+        },
+        |frame, sprites| {
+            // Submitted in the reverse order of the layers they end up on
+            frame
+                .draw_sprite(sprites[2])
+                .layer("foreground")
+                .pos([0.5, 0., 0.])
+                .done();
+            frame.draw_sprite(sprites[1]).pos([0., 0., 0.]).done();
+            frame
+                .draw_sprite(sprites[0])
+                .layer("background")
+                .pos([-0.5, 0., 0.])
+                .scale(2.)
+                .done();
+        },
+    );
+
+    assert_golden("layer_ordering", &frame, Tolerance::default());
+}
+
+#[test]
+fn shapes() {
+    let frame = render(
+        |renderer| {
+            renderer.set_layer("foreground", 1);
+            quickstart_sprites(renderer)
+        },
+        |frame, sprites| {
+            frame
+                .draw_rect([2., 1.5])
+                .pos([-1.5, 1.5, 0.])
+                .fill([255, 64, 0])
+                .outline([255, 255, 255], 0.2)
+                .done();
+            frame
+                .draw_circle(1.)
+                .pos([1.5, 1.5, 0.])
+                .no_fill()
+                .outline([0, 128, 255], 0.15)
+                .done();
+            frame
+                .draw_polygon([[0., 1.], [-1., -0.5], [1., -0.5]])
+                .pos([-1.5, -1.5, 0.])
+                .rotate(std::f32::consts::FRAC_PI_4)
+                .opacity(0.5)
+                .done();
+            // The line is on a layer above the sprite it crosses
+            frame
+                .draw_line([-1., -1.], [1., 1.], 0.25)
+                .layer("foreground")
+                .pos([1.5, -1.5, 0.])
+                .fill([0, 255, 64])
+                .done();
+            frame.draw_sprite(sprites[0]).pos([1.5, -1.5, 0.]).done();
+        },
+    );
+
+    assert_golden("shapes", &frame, Tolerance::default());
+}
+
+#[test]
+fn nine_slice() {
+    let frame = render(
+        |renderer| {
+            let bordered = SpriteLoadOptions {
+                nine_slice: Some([4; 4]),
+                ..Default::default()
+            };
+            renderer
+                .atlas()
+                .add_sprite_advanced(load_asset("16x16.png"), bordered)
+                .finalize()
+                .to_vec()
+        },
+        |frame, sprites| {
+            frame
+                .draw_sprite(sprites[0])
+                .pos([0., 1.5, 0.])
+                .nine_slice([3.5, 1.5], NineSliceFill::Stretch)
+                .done();
+            frame
+                .draw_sprite(sprites[0])
+                .pos([0., -1.5, 0.])
+                .nine_slice([3.5, 1.5], NineSliceFill::Tile)
+                .done();
+        },
+    );
+
+    assert_golden("nine_slice", &frame, Tolerance::default());
+}
+
+#[test]
+fn tilemap() {
+    let mut renderer = Renderer::headless(FRAME_SIZE);
+    renderer.set_layer("background", -1);
+    let sprites = quickstart_sprites(&mut renderer);
+
+    // Larger than a chunk and than the view, so that some chunks are culled
+    let mut tilemap = Tilemap::new(40, 40, [0.5, 0.5])
+        .pos([-3., 3., 0.])
+        .layer("background");
+    tilemap.fill((0..40 * 40).map(|idx| match (idx % 40 + idx / 40) % 3 {
+        0 => None,
+        tile => Some(sprites[tile - 1]),
+    }));
+    tilemap.update(&renderer).unwrap();
+    // Only the changed chunk is rebuilt
+    tilemap.set_tile(1, 1, Some(sprites[2]));
+    tilemap.update(&renderer).unwrap();
+    // An unknown sprite fails the update until the tile is fixed
+    tilemap.set_tile(39, 39, Some(sprites.len()));
+    assert!(matches!(
+        tilemap.update(&renderer),
+        Err(TangerineError::UnknownSprite(_))
+    ));
+    tilemap.set_tile(39, 39, None);
+    tilemap.update(&renderer).unwrap();
+
+    let mut frame = FrameBuilder::new_global(renderer.viewport());
+    frame.draw_sprite(sprites[0]).done();
+    frame.draw_tilemap(&tilemap);
+    renderer.draw_frame(frame.finalize()).unwrap();
+
+    assert_golden(
+        "tilemap",
+        &renderer.read_frame().unwrap().unwrap(),
+        Tolerance::default(),
+    );
+}
+
+#[test]
+fn camera_transform() {
+    let frame = render(
+        |renderer| {
+            renderer.mutate_camera(|camera| {
+                camera.position = [1., 0.5].into();
+                camera.rotation_rad = std::f32::consts::FRAC_PI_6;
+                camera.zoom = 1.5;
+            });
+            quickstart_sprites(renderer)
+        },
+        |frame, sprites| {
+            frame.draw_sprite(sprites[0]).pos([1., 0.5, 0.]).done();
+            frame.draw_sprite(sprites[1]).pos([-1., 0., 0.]).done();
+            frame.draw_sprite(sprites[2]).pos([2.5, 1.5, 0.]).done();
+        },
+    );
+
+    assert_golden("camera_transform", &frame, Tolerance::default());
+}
+
+#[test]
+fn split_screen() {
+    let frame = render(
+        |renderer| {
+            renderer.set_layer("hud", 10);
+            renderer
+                .set_camera_viewport(MAIN_CAMERA, [0., 0., 0.5, 1.])
+                .unwrap();
+            renderer.add_camera(
+                "player_two",
+                Camera {
+                    position: [2., 0.].into(),
+                    ..Default::default()
+                },
+                [0.5, 0., 0.5, 1.],
+            );
+            // A minimap in the bottom right corner without the HUD
+            renderer.add_camera(
+                "minimap",
+                Camera {
+                    size: 6.,
+                    ..Default::default()
+                },
+                [0.75, 0.75, 0.25, 0.25],
+            );
+            renderer
+                .set_camera_layers("minimap", LayerMask::except(["hud"]))
+                .unwrap();
+
+            assert!(matches!(
+                renderer.set_camera_layers("missing", LayerMask::All),
+                Err(TangerineError::UnknownCamera(_))
+            ));
+            assert_eq!(
+                renderer.named_camera("player_two").unwrap().aspect_ratio,
+                0.5
+            );
+            quickstart_sprites(renderer)
+        },
+        |frame, sprites| {
+            frame.draw_sprite(sprites[0]).pos([0., 0., 0.]).done();
+            frame.draw_sprite(sprites[1]).pos([2., 0., 0.]).done();
+            frame
+                .draw_rect([1.5, 0.5])
+                .layer("hud")
+                .pos([0., 2.5, 0.])
+                .fill([255, 64, 0])
+                .done();
+        },
+    );
+
+    assert_golden("split_screen", &frame, Tolerance::default());
+}
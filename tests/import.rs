@@ -0,0 +1,130 @@
+use chrono::Duration;
+use image::RgbaImage;
+use tangerine::{AnimationMode, ImportedSheet, Renderer, SpriteRect, TangerineError};
+
+#[test]
+fn aseprite_hash() {
+    let sheet = ImportedSheet::from_json(
+        r#"{
+            "frames": {
+                "walk 1.aseprite": {
+                    "frame": { "x": 0, "y": 0, "w": 16, "h": 16 },
+                    "rotated": false,
+                    "trimmed": false,
+                    "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 },
+                    "sourceSize": { "w": 16, "h": 16 },
+                    "duration": 120
+                },
+                "walk 0.aseprite": {
+                    "frame": { "x": 16, "y": 0, "w": 16, "h": 16 },
+                    "rotated": false,
+                    "trimmed": false,
+                    "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 },
+                    "sourceSize": { "w": 16, "h": 16 },
+                    "duration": 80
+                }
+            },
+            "meta": {
+                "image": "walk.png",
+                "frameTags": [
+                    { "name": "walk", "from": 0, "to": 1, "direction": "pingpong" }
+                ]
+            }
+        }"#,
+    )
+    .unwrap();
+
+    // The frames keep the order of the file, not the alphabetical one
+    assert_eq!(sheet.frames[0].name, "walk 1.aseprite");
+    assert_eq!(sheet.frames[1].rect, SpriteRect::new(16, 0, 16, 16));
+    assert_eq!(sheet.frames[1].duration, Some(Duration::milliseconds(80)));
+    assert_eq!(sheet.image.as_deref(), Some("walk.png".as_ref()));
+
+    assert_eq!(sheet.tags.len(), 1);
+    assert_eq!(sheet.tags[0].mode, AnimationMode::PingPong);
+    assert_eq!((sheet.tags[0].from, sheet.tags[0].to), (0, 1));
+}
+
+#[test]
+fn texture_packer_array() {
+    let sheet = ImportedSheet::from_json(
+        r#"{
+            "frames": [
+                {
+                    "filename": "ship.png",
+                    "frame": { "x": 2, "y": 2, "w": 10, "h": 6 },
+                    "rotated": true,
+                    "trimmed": true,
+                    "spriteSourceSize": { "x": 3, "y": 5, "w": 10, "h": 6 },
+                    "sourceSize": { "w": 16, "h": 16 },
+                    "pivot": { "x": 0.5, "y": 1 }
+                }
+            ],
+            "meta": { "image": "ships.png" }
+        }"#,
+    )
+    .unwrap();
+
+    let ship = &sheet.frames[0];
+    assert_eq!(ship.name, "ship.png");
+    assert!(ship.rotated);
+    assert_eq!(ship.source_size, [16, 16]);
+    assert_eq!(ship.trimmed_offset, [3, 5]);
+    assert_eq!(ship.pivot, Some([0.5, 1.]));
+    assert_eq!(ship.duration, None);
+    assert!(sheet.tags.is_empty());
+}
+
+#[test]
+fn invalid_tag() {
+    let result = ImportedSheet::from_json(
+        r#"{
+            "frames": [],
+            "meta": { "frameTags": [{ "name": "idle", "from": 0, "to": 3 }] }
+        }"#,
+    );
+    assert!(matches!(result, Err(TangerineError::MalformedSheet(_))));
+}
+
+#[test]
+fn frame_outside_of_image() {
+    let sheet = ImportedSheet::from_json(
+        r#"{
+            "frames": [{
+                "filename": "tall",
+                "frame": { "x": 8, "y": 0, "w": 8, "h": 16 },
+                "rotated": true
+            }],
+            "meta": {}
+        }"#,
+    )
+    .unwrap();
+
+    // Turned on its side the frame is 16 pixels wide
+    let mut renderer = Renderer::headless([16, 16]);
+    let result = renderer
+        .atlas()
+        .add_imported_sheet(RgbaImage::new(16, 16), &sheet);
+    assert!(matches!(result, Err(TangerineError::MalformedSheet(_))));
+}
+
+#[test]
+fn empty_frames() {
+    let mut renderer = Renderer::headless([16, 16]);
+
+    for frame in [
+        r#"{ "frame": { "x": 0, "y": 0, "w": 0, "h": 8 } }"#,
+        r#"{ "frame": { "x": 0, "y": 0, "w": 8, "h": 8 }, "sourceSize": { "w": 8, "h": 0 } }"#,
+    ] {
+        let sheet =
+            ImportedSheet::from_json(&format!(r#"{{ "frames": [{frame}], "meta": {{}} }}"#))
+                .unwrap();
+        let result = renderer
+            .atlas()
+            .add_imported_sheet(RgbaImage::new(16, 16), &sheet);
+        assert!(
+            matches!(result, Err(TangerineError::MalformedSheet(_))),
+            "{frame}"
+        );
+    }
+}
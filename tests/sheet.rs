@@ -0,0 +1,43 @@
+use image::RgbaImage;
+use tangerine::{Renderer, SpriteRect, SpriteSheet, TangerineError};
+
+#[test]
+fn grid_without_trailing_margin() {
+    // Tiled only puts the margin before the first tile
+    let sheet = SpriteSheet::grid(16, 16).margin(1, 1).spacing(2, 2);
+    let slices = sheet.slices(1 + 16 + 2 + 16, 1 + 16);
+
+    assert_eq!(
+        slices,
+        vec![
+            SpriteRect::new(1, 1, 16, 16),
+            SpriteRect::new(19, 1, 16, 16)
+        ]
+    );
+}
+
+#[test]
+fn grid_of_empty_cells() {
+    assert!(SpriteSheet::grid(0, 0).slices(16, 16).is_empty());
+    assert!(SpriteSheet::grid(8, 0)
+        .spacing(1, 1)
+        .slices(16, 16)
+        .is_empty());
+}
+
+#[test]
+fn slice_outside_of_sheet() {
+    let mut renderer = Renderer::headless([16, 16]);
+    let result = renderer.atlas().add_sprite_sheet(
+        RgbaImage::new(16, 16),
+        &SpriteSheet::rects([[0, 0, 8, 8], [12, 0, 8, 8]]),
+    );
+
+    assert!(matches!(
+        result,
+        Err(TangerineError::InvalidSlice {
+            slice: SpriteRect { x: 12, .. },
+            sheet_size: [16, 16],
+        })
+    ));
+}
@@ -0,0 +1,153 @@
+use image::RgbaImage;
+use tangerine::{BmFont, Font, Glyph, Renderer, SpriteRect, TangerineError, TextAlign, TextLayout};
+
+/// Every glyph is 10 pixels wide with lines 20 pixels tall, "AV" is kerned.
+struct FixedFont(Glyph);
+
+impl Font for FixedFont {
+    fn glyph(&self, character: char) -> Option<&Glyph> {
+        (character != '#').then_some(&self.0)
+    }
+
+    fn kerning(&self, left: char, right: char) -> f32 {
+        if (left, right) == ('A', 'V') {
+            -2.
+        } else {
+            0.
+        }
+    }
+
+    fn line_height(&self) -> f32 {
+        20.
+    }
+}
+
+fn fixed_font() -> FixedFont {
+    FixedFont(Glyph {
+        sprite: Some(0),
+        offset: [1., 2.],
+        advance: 10.,
+    })
+}
+
+fn positions(layout: TextLayout, text: &str) -> Vec<[f32; 2]> {
+    layout
+        .layout(&fixed_font(), text)
+        .into_iter()
+        .map(|glyph| glyph.position)
+        .collect()
+}
+
+#[test]
+fn parse_bmfont() {
+    let font = BmFont::parse(
+        r#"info face="Pixel Font" size=16 bold=0
+common lineHeight=18 base=14 scaleW=64 scaleH=64 pages=1
+page id=0 file="pixel font_0.png"
+chars count=2
+char id=65 x=1 y=2 width=7 height=9 xoffset=0 yoffset=3 xadvance=8 page=0 chnl=15
+char id=32 x=0 y=0 width=0 height=0 xoffset=0 yoffset=0 xadvance=4 page=0 chnl=15
+kernings count=1
+kerning first=65 second=86 amount=-1
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(font.line_height, 18.);
+    assert_eq!(font.pages, vec![String::from("pixel font_0.png")]);
+    assert_eq!(font.chars[0].character, 'A');
+    assert_eq!(font.chars[0].rect, SpriteRect::new(1, 2, 7, 9));
+    assert_eq!(font.chars[0].offset, [0., 3.]);
+    assert_eq!(font.chars[1].advance, 4.);
+    assert_eq!(font.kernings, [('A', 'V', -1.)]);
+}
+
+#[test]
+fn kerning_and_line_breaks() {
+    assert_eq!(
+        positions(Default::default(), "AV\nA#A"),
+        [[1., 2.], [9., 2.], [1., 22.], [11., 22.]]
+    );
+}
+
+#[test]
+fn alignment() {
+    let centered = TextLayout {
+        align: TextAlign::Center,
+        ..Default::default()
+    };
+    assert_eq!(positions(centered, "ab"), [[-9., 2.], [1., 2.]]);
+
+    let right = TextLayout {
+        align: TextAlign::Right,
+        ..Default::default()
+    };
+    assert_eq!(positions(right, "ab"), [[-19., 2.], [-9., 2.]]);
+}
+
+#[test]
+fn wrapping() {
+    let layout = TextLayout {
+        max_width: Some(50.),
+        ..Default::default()
+    };
+    let lines = |text| {
+        positions(layout, text)
+            .into_iter()
+            .map(|[_, y]| ((y - 2.) / 20.) as usize)
+            .collect::<Vec<_>>()
+    };
+
+    // "ab cd" fits, "ef" goes onto the next line
+    assert_eq!(lines("ab cd ef"), [0, 0, 0, 0, 0, 1, 1]);
+    // Words longer than a line are broken
+    assert_eq!(lines("abcdefg"), [0, 0, 0, 0, 0, 1, 1]);
+}
+
+#[test]
+fn bmfont_page_beyond_page_count() {
+    let result = BmFont::parse(
+        r#"common lineHeight=18 base=14 scaleW=64 scaleH=64 pages=1
+page id=4000000000 file="huge.png"
+"#,
+    );
+
+    assert!(matches!(result, Err(TangerineError::InvalidFont(_))));
+}
+
+#[test]
+fn bmfont_glyphs_outside_of_pages() {
+    let font = |char_line: &str| {
+        BmFont::parse(&format!(
+            "common lineHeight=18 base=14 scaleW=16 scaleH=16 pages=1\n{char_line}\n"
+        ))
+        .unwrap()
+    };
+    let mut renderer = Renderer::headless([16, 16]);
+
+    for char_line in [
+        "char id=65 x=0 y=0 width=8 height=8 xoffset=0 yoffset=0 xadvance=8 page=1",
+        "char id=65 x=12 y=0 width=8 height=8 xoffset=0 yoffset=0 xadvance=8 page=0",
+        "char id=65 x=4294967295 y=0 width=2 height=8 xoffset=0 yoffset=0 xadvance=8 page=0",
+    ] {
+        let result = renderer
+            .atlas()
+            .add_bmfont([RgbaImage::new(16, 16)], &font(char_line));
+        assert!(matches!(result, Err(TangerineError::InvalidFont(_))));
+    }
+}
+
+#[test]
+fn monospace_font_with_too_many_chars() {
+    let mut renderer = Renderer::headless([16, 16]);
+
+    let result = renderer
+        .atlas()
+        .add_monospace_font(RgbaImage::new(16, 8), 8, 8, "abc".chars());
+    assert!(matches!(result, Err(TangerineError::InvalidFont(_))));
+
+    let result = renderer
+        .atlas()
+        .add_monospace_font(RgbaImage::new(16, 8), 0, 8, "a".chars());
+    assert!(matches!(result, Err(TangerineError::InvalidFont(_))));
+}
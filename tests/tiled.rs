@@ -0,0 +1,185 @@
+#![cfg(feature = "tiled")]
+
+use image::RgbaImage;
+use tangerine::{
+    Renderer, TangerineError, TiledLayerContents, TiledMap, TiledProperty, TiledShape, TiledSprites,
+};
+
+const TMX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" width="2" height="2" tilewidth="16" tileheight="16" infinite="0">
+ <properties>
+  <property name="music" value="cave.ogg"/>
+ </properties>
+ <tileset firstgid="1" name="terrain" tilewidth="16" tileheight="16" spacing="1" margin="1" tilecount="4" columns="2">
+  <image source="terrain.png" width="35" height="35"/>
+ </tileset>
+ <layer id="1" name="ground" width="2" height="2">
+  <data encoding="csv">
+1,2,
+0,3
+</data>
+ </layer>
+ <group id="2" name="details" offsetx="4" visible="0">
+  <layer id="3" name="decoration" width="2" height="2" offsety="2">
+   <data encoding="base64">AQAAAAIAAAAAAAAAAwAAgA==</data>
+  </layer>
+ </group>
+ <objectgroup id="4" name="entities">
+  <object id="1" name="spawn" type="player" x="8" y="24">
+   <properties>
+    <property name="health" type="int" value="3"/>
+    <property name="flying" type="bool" value="true"/>
+   </properties>
+   <point/>
+  </object>
+  <object id="2" class="trigger" x="0" y="0" width="32" height="16"/>
+  <object id="3" x="4" y="4">
+   <polygon points="0,0 8,0 4,-6"/>
+  </object>
+ </objectgroup>
+</map>
+"#;
+
+const JSON: &str = r#"{
+  "width": 2, "height": 2, "tilewidth": 16, "tileheight": 16, "infinite": false,
+  "properties": [{ "name": "music", "type": "string", "value": "cave.ogg" }],
+  "tilesets": [{
+    "firstgid": 1, "name": "terrain", "image": "terrain.png", "tilewidth": 16,
+    "tileheight": 16, "spacing": 1, "margin": 1, "tilecount": 4, "columns": 2
+  }],
+  "layers": [
+    { "type": "tilelayer", "name": "ground", "width": 2, "height": 2, "data": [1, 2, 0, 3] },
+    { "type": "group", "name": "details", "offsetx": 4, "visible": false, "layers": [
+      { "type": "tilelayer", "name": "decoration", "width": 2, "height": 2, "offsety": 2,
+        "encoding": "base64", "data": "AQAAAAIAAAAAAAAAAwAAgA==" }
+    ]},
+    { "type": "objectgroup", "name": "entities", "objects": [
+      { "id": 1, "name": "spawn", "type": "player", "x": 8, "y": 24, "point": true,
+        "properties": [
+          { "name": "health", "type": "int", "value": 3 },
+          { "name": "flying", "type": "bool", "value": true }
+        ] },
+      { "id": 2, "class": "trigger", "x": 0, "y": 0, "width": 32, "height": 16 },
+      { "id": 3, "x": 4, "y": 4, "polygon": [{ "x": 0, "y": 0 }, { "x": 8, "y": 0 }, { "x": 4, "y": -6 }] }
+    ]}
+  ]
+}"#;
+
+#[test]
+fn tmx_and_json_match() {
+    assert_eq!(
+        TiledMap::from_tmx(TMX).unwrap(),
+        TiledMap::from_json(JSON).unwrap()
+    );
+}
+
+#[test]
+fn layers() {
+    let map = TiledMap::from_tmx(TMX).unwrap();
+
+    assert_eq!((map.columns, map.rows), (2, 2));
+    assert_eq!(map.tilesets[0].image, std::path::Path::new("terrain.png"));
+    assert_eq!((map.tilesets[0].margin, map.tilesets[0].spacing), (1, 1));
+    assert_eq!(
+        map.properties["music"],
+        TiledProperty::String("cave.ogg".into())
+    );
+
+    let names = map.layers.iter().map(|layer| layer.name.as_str());
+    assert!(names.eq(["ground", "decoration", "entities"]));
+
+    // The group's offset and visibility carry over to its layers
+    let decoration = &map.layers[1];
+    assert_eq!(decoration.offset, [4., 2.]);
+    assert!(!decoration.visible);
+    assert_eq!(
+        decoration.contents,
+        TiledLayerContents::Tiles {
+            columns: 2,
+            rows: 2,
+            gids: vec![1, 2, 0, 0x8000_0003],
+        }
+    );
+}
+
+#[test]
+fn objects() {
+    let map = TiledMap::from_tmx(TMX).unwrap();
+    let objects = map.objects("entities").unwrap();
+
+    assert_eq!(objects[0].name, "spawn");
+    assert_eq!(objects[0].class, "player");
+    assert_eq!(objects[0].shape, TiledShape::Point);
+    assert_eq!(objects[0].properties["health"], TiledProperty::Int(3));
+    assert_eq!(objects[0].properties["flying"], TiledProperty::Bool(true));
+
+    assert_eq!(objects[1].class, "trigger");
+    assert_eq!(objects[1].shape, TiledShape::Rect);
+    assert_eq!(objects[1].size, [32., 16.]);
+
+    assert_eq!(
+        objects[2].shape,
+        TiledShape::Polygon(vec![[0., 0.], [8., 0.], [4., -6.]])
+    );
+    let spawn = TiledMap::to_world(objects[0].position, 16.);
+    assert_eq!([spawn.x, spawn.y], [0.5, -1.5]);
+}
+
+#[test]
+fn tile_sprites() {
+    let sprites = TiledSprites {
+        tilesets: vec![(1, vec![10, 11, 12]), (4, vec![20, 21])],
+    };
+
+    assert_eq!(sprites.sprite(0), None);
+    assert_eq!(sprites.sprite(2), Some(11));
+    assert_eq!(sprites.sprite(5), Some(21));
+    // Flipped tiles use the same sprite
+    assert_eq!(sprites.sprite(0x8000_0004), Some(20));
+    assert_eq!(sprites.sprite(6), None);
+}
+
+#[test]
+fn one_image_per_tileset() {
+    let map = TiledMap::from_tmx(TMX).unwrap();
+    let mut renderer = Renderer::headless([16, 16]);
+
+    let result = renderer
+        .atlas()
+        .add_tiled_map(Vec::<RgbaImage>::new(), &map);
+    assert!(matches!(result, Err(TangerineError::InvalidMap(_))));
+
+    let images = [RgbaImage::new(35, 35), RgbaImage::new(35, 35)];
+    let result = renderer.atlas().add_tiled_map(images, &map);
+    assert!(matches!(result, Err(TangerineError::InvalidMap(_))));
+
+    let result = renderer
+        .atlas()
+        .add_tiled_map([RgbaImage::new(35, 35)], &map);
+    assert_eq!(result.unwrap().1.sprite(4), Some(3));
+}
+
+#[test]
+fn tileset_larger_than_its_image() {
+    let map = TiledMap::from_tmx(TMX).unwrap();
+    let mut renderer = Renderer::headless([16, 16]);
+
+    // Only the top row of the 2x2 tiles fits
+    let result = renderer
+        .atlas()
+        .add_tiled_map([RgbaImage::new(35, 18)], &map);
+    assert!(matches!(result, Err(TangerineError::InvalidMap(_))));
+}
+
+#[test]
+fn external_tilesets_need_a_file() {
+    let map = TMX.replace(
+        r#"<tileset firstgid="1" name="terrain""#,
+        r#"<tileset firstgid="1" source="terrain.tsx"/><tileset firstgid="5" name="terrain""#,
+    );
+
+    assert!(matches!(
+        TiledMap::from_tmx(&map),
+        Err(TangerineError::InvalidMap(_))
+    ));
+}
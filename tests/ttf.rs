@@ -0,0 +1,112 @@
+#![cfg(feature = "ttf")]
+
+use std::path::PathBuf;
+
+use image::RgbaImage;
+use tangerine::{
+    Font, FrameBuilder, Renderer, RendererBuilder, SpriteIndex, TangerineError, TrueTypeFont,
+};
+
+const PIXEL_SIZE: f32 = 32.;
+
+fn cantarell() -> TrueTypeFont {
+    let path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("examples/assets/Cantarell-Regular.ttf");
+    TrueTypeFont::load(path, PIXEL_SIZE).unwrap()
+}
+
+fn draw(renderer: &mut Renderer, font: &TrueTypeFont, text: &str) -> RgbaImage {
+    let mut frame = FrameBuilder::new_global(renderer.viewport());
+    frame
+        .draw_text(font, text)
+        .pos([-3., 1., 0.])
+        .size(2.)
+        .done();
+    renderer.draw_frame(frame.finalize()).unwrap();
+    renderer.read_frame().unwrap().unwrap()
+}
+
+#[test]
+fn rasterization_and_metrics() {
+    let mut renderer = Renderer::headless([64, 64]);
+    let mut font = cantarell();
+    assert!(font.glyph('l').is_none());
+
+    font.cache_glyphs(&mut renderer, "l.  ").unwrap();
+    let sizes = renderer.atlas_manifest("atlas").sprites;
+    let size = |character: char| {
+        let sprite = &sizes[font.glyph(character).unwrap().sprite.unwrap()];
+        [sprite.width, sprite.height]
+    };
+
+    let [l_width, l_height] = size('l');
+    let [dot_width, dot_height] = size('.');
+    assert!(l_height > l_width && l_height > dot_height && l_height <= PIXEL_SIZE as u32);
+    assert!(dot_width < l_height / 2 && dot_height < l_height / 2);
+    // The dot sits on the baseline, at the bottom of the stem of the l
+    let [l, dot] = ['l', '.'].map(|character| font.glyph(character).unwrap().offset);
+    assert!((l[1] + l_height as f32 - dot[1] - dot_height as f32).abs() <= 1.);
+
+    let space = font.glyph(' ').unwrap();
+    assert_eq!(space.sprite, None);
+    assert!(space.advance > 0. && space.advance < PIXEL_SIZE);
+    assert!(font.line_height() >= PIXEL_SIZE);
+}
+
+#[test]
+fn caching_keeps_earlier_glyphs() {
+    let mut renderer = Renderer::headless([64, 64]);
+    let mut font = cantarell();
+
+    font.cache_glyphs(&mut renderer, "Hi").unwrap();
+    let drawn = draw(&mut renderer, &font, "Hi");
+    assert!(drawn.pixels().any(|pixel| pixel.0[3] > 0x80));
+    let glyphs = ['H', 'i'].map(|character| *font.glyph(character).unwrap());
+    let pixels = glyphs.map(|glyph| sprite_pixels(&renderer, glyph.sprite.unwrap()));
+
+    font.cache_glyphs(&mut renderer, "Hello, world! ABCDEFGHIJKLMNOPQRSTUVWXYZ")
+        .unwrap();
+    assert_eq!(
+        ['H', 'i'].map(|character| *font.glyph(character).unwrap()),
+        glyphs
+    );
+    assert!(font.glyph('w').unwrap().sprite > glyphs[1].sprite);
+    assert_eq!(
+        glyphs.map(|glyph| sprite_pixels(&renderer, glyph.sprite.unwrap())),
+        pixels
+    );
+
+    let drawn = draw(&mut renderer, &font, "Hello, world!");
+    assert!(drawn.pixels().any(|pixel| pixel.0[3] > 0x80));
+}
+
+/// The pixels of the sprite as they are stored in its atlas page.
+fn sprite_pixels(renderer: &Renderer, sprite: SpriteIndex) -> RgbaImage {
+    let dir = std::env::temp_dir().join("tangerine-ttf");
+    std::fs::create_dir_all(&dir).unwrap();
+    renderer.save_atlas(dir.join("atlas.json")).unwrap();
+
+    let manifest = renderer.atlas_manifest("atlas");
+    let sprite = &manifest.sprites[sprite];
+    let page = image::open(dir.join(&manifest.pages[sprite.page].image)).unwrap();
+    page.crop_imm(sprite.x, sprite.y, sprite.width, sprite.height)
+        .into_rgba8()
+}
+
+#[test]
+fn glyph_larger_than_atlas() {
+    let mut renderer = RendererBuilder::default()
+        .limits(wgpu::Limits {
+            max_texture_dimension_2d: 16,
+            ..wgpu::Limits::downlevel_defaults()
+        })
+        .build_headless([16, 16])
+        .unwrap();
+    let mut font = cantarell();
+
+    let result = font.cache_glyphs(&mut renderer, "H ");
+    assert!(matches!(result, Err(TangerineError::SpriteTooLarge { .. })));
+    // Neither the glyph that didn't fit nor the others of the text are cached
+    assert!(font.glyph('H').is_none() && font.glyph(' ').is_none());
+    assert!(renderer.atlas_manifest("atlas").sprites.is_empty());
+}